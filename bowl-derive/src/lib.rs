@@ -0,0 +1,185 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Field, Fields, Index, Member};
+
+/// Derives `bowl::data::vertex_buffer_layout::VertexLayout` for a `#[repr(C)]` struct.
+///
+/// Every field becomes one attribute, in declaration order, with the type, count and offset of the field.
+/// The type of each field must implement `bowl::data::vertex_buffer_layout::VertexAttribute`.
+/// Fields marked with `#[vertex(normalized)]` have their integer components normalized to floats.
+#[proc_macro_derive(VertexLayout, attributes(vertex))]
+pub fn derive_vertex_layout(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match vertex_layout(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn vertex_layout(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    if !has_repr_c(input)? {
+        return Err(Error::new_spanned(&input.ident, "VertexLayout can only be derived for #[repr(C)] structs, as the field order and offsets must be stable"));
+    }
+
+    let fields = struct_fields(input, "VertexLayout")?;
+
+    let mut attributes = Vec::new();
+    for (member, field) in &fields {
+        let ty = &field.ty;
+        let normalized = is_normalized(field)?;
+
+        attributes.push(quote! {
+            layout.add(
+                ::bowl::data::vertex_buffer_layout::AttributeDesc::new(
+                    <#ty as ::bowl::data::vertex_buffer_layout::VertexAttribute>::TYPE,
+                    <#ty as ::bowl::data::vertex_buffer_layout::VertexAttribute>::COUNT,
+                )
+                .columns(<#ty as ::bowl::data::vertex_buffer_layout::VertexAttribute>::COLUMNS)
+                .offset(::core::mem::offset_of!(Self, #member) as u32)
+                .normalized(#normalized)
+            );
+        });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::bowl::data::vertex_buffer_layout::VertexLayout for #name #type_generics #where_clause {
+            fn layout() -> ::bowl::data::vertex_buffer_layout::VertexBufferLayout {
+                let mut layout = ::bowl::data::vertex_buffer_layout::VertexBufferLayout::with_stride(::core::mem::size_of::<Self>() as u32);
+                #(#attributes)*
+                layout
+            }
+        }
+    })
+}
+
+/// Derives `bowl::data::std140::Std140` for a struct.
+///
+/// The fields are laid out in declaration order according to the std140 rules of uniform blocks, independent of
+/// the Rust layout of the struct. The type of each field must implement `bowl::data::std140::Std140`.
+#[proc_macro_derive(Std140)]
+pub fn derive_std140(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match std140(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn std140(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let fields = struct_fields(input, "Std140")?;
+
+    let mut alignments = Vec::new();
+    let mut offsets = Vec::new();
+    let mut writes = Vec::new();
+    for (member, field) in &fields {
+        let ty = &field.ty;
+
+        alignments.push(quote! {
+            let align = ::bowl::data::std140::max(align, <#ty as ::bowl::data::std140::Std140>::ALIGN);
+        });
+        offsets.push(quote! {
+            let offset = ::bowl::data::std140::align_to(offset, <#ty as ::bowl::data::std140::Std140>::ALIGN)
+                + <#ty as ::bowl::data::std140::Std140>::SIZE;
+        });
+        writes.push(quote! {
+            let offset = ::bowl::data::std140::align_to(offset, <#ty as ::bowl::data::std140::Std140>::ALIGN);
+            ::bowl::data::std140::Std140::write_std140(&self.#member, &mut buffer[offset..offset + <#ty as ::bowl::data::std140::Std140>::SIZE]);
+            let offset = offset + <#ty as ::bowl::data::std140::Std140>::SIZE;
+        });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::bowl::data::std140::Std140 for #name #type_generics #where_clause {
+            // Structs are aligned to at least the alignment of vec4
+            const ALIGN: usize = {
+                let align = 16;
+                #(#alignments)*
+                align
+            };
+
+            const SIZE: usize = {
+                let offset = 0;
+                #(#offsets)*
+                ::bowl::data::std140::align_to(offset, Self::ALIGN)
+            };
+
+            fn write_std140(&self, buffer: &mut [u8]) {
+                let offset = 0;
+                #(#writes)*
+                let _ = offset;
+            }
+        }
+    })
+}
+
+/// The fields of a struct with the member to access them, in declaration order
+fn struct_fields<'a>(input: &'a DeriveInput, derive: &str) -> Result<Vec<(Member, &'a Field)>, Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => return Err(Error::new_spanned(&input.ident, format!("{} can only be derived for structs", derive))),
+    };
+
+    let fields: Vec<_> = match fields {
+        Fields::Named(fields) => fields.named.iter()
+            .map(|field| (Member::Named(field.ident.clone().unwrap()), field))
+            .collect(),
+        Fields::Unnamed(fields) => fields.unnamed.iter()
+            .enumerate()
+            .map(|(index, field)| (Member::Unnamed(Index::from(index)), field))
+            .collect(),
+        Fields::Unit => Vec::new(),
+    };
+
+    if fields.is_empty() {
+        return Err(Error::new_spanned(&input.ident, format!("{} requires at least one field", derive)));
+    }
+
+    Ok(fields)
+}
+
+fn is_normalized(field: &Field) -> Result<bool, Error> {
+    let mut normalized = false;
+
+    for attribute in field.attrs.iter().filter(|attribute| attribute.path().is_ident("vertex")) {
+        attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("normalized") {
+                normalized = true;
+                Ok(())
+            } else {
+                Err(meta.error("unknown vertex attribute option, expected `normalized`"))
+            }
+        })?;
+    }
+
+    Ok(normalized)
+}
+
+fn has_repr_c(input: &DeriveInput) -> Result<bool, Error> {
+    let mut repr_c = false;
+
+    for attribute in input.attrs.iter().filter(|attribute| attribute.path().is_ident("repr")) {
+        attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("C") {
+                repr_c = true;
+            }
+            // Skip the arguments of other representations like align(16)
+            if meta.input.peek(syn::token::Paren) {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                content.parse::<TokenStream2>()?;
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(repr_c)
+}
@@ -0,0 +1,55 @@
+use glam::{Vec2, Vec3};
+
+use bowl::renderable::Mesh;
+use bowl::shader::{memory_barrier, MemoryBarriers, Shader, ShaderProgram};
+use bowl::shader::ShaderType::{COMPUTE, FRAGMENT, VERTEX};
+use bowl::texture::{ImageAccess, Texture2D, TextureFormat};
+use bowl::vertex::Vertex;
+
+const IMAGE_SIZE: u32 = 512;
+const WORK_GROUP_SIZE: u32 = 16;
+
+fn main() {
+    // Compute shaders require OpenGL 4.3
+    let window = bowl::window::WindowBuilder::new()
+        .size(800, 800)
+        .title(String::from("Compute Shader Example"))
+        .gl_version(4, 3)
+        .create()
+        .expect("Could not create bowl window with OpenGL 4.3!");
+
+    let quad = Mesh::new([
+        (Vec3::new(-1.0, -1.0, 0.0), Vec2::new(0.0, 0.0)),
+        (Vec3::new(1.0, -1.0, 0.0), Vec2::new(1.0, 0.0)),
+        (Vec3::new(1.0, 1.0, 0.0), Vec2::new(1.0, 1.0)),
+        (Vec3::new(-1.0, -1.0, 0.0), Vec2::new(0.0, 0.0)),
+        (Vec3::new(1.0, 1.0, 0.0), Vec2::new(1.0, 1.0)),
+        (Vec3::new(-1.0, 1.0, 0.0), Vec2::new(0.0, 1.0)),
+    ].iter().map(|(position, texture_coordinates)| Vertex::from(*position, Vec3::splat(1.0), *texture_coordinates)).collect());
+
+    let compute_shader = Shader::new(COMPUTE, include_str!("./shader.comp")).expect("Could not compile compute shader!");
+    let compute_program = ShaderProgram::new(vec![compute_shader]).expect("Could not link compute program!");
+
+    let vertex_shader = Shader::new(VERTEX, include_str!("./shader.vert")).expect("Could not compile vertex shader!");
+    let fragment_shader = Shader::new(FRAGMENT, include_str!("./shader.frag")).expect("Could not compile fragment shader!");
+    let program = ShaderProgram::new(vec![vertex_shader, fragment_shader]).expect("Could not link shader program!");
+
+    let texture = Texture2D::empty(IMAGE_SIZE, IMAGE_SIZE, TextureFormat::RGBA8);
+
+    let mut time: f32 = 0.0;
+    window.run(|handle| {
+        time += handle.dtime as f32 / 1_000_000.0;
+
+        compute_program.bind();
+        compute_program.set_uniform("time", time).expect("Could not set time!");
+        compute_program.bind_image("outputImage", &texture, 0, ImageAccess::WRITE_ONLY).expect("Could not bind image!");
+        compute_program.dispatch(IMAGE_SIZE / WORK_GROUP_SIZE, IMAGE_SIZE / WORK_GROUP_SIZE, 1).expect("Could not dispatch compute shader!");
+
+        // The image stores must be finished before the texture is sampled
+        memory_barrier(MemoryBarriers::TEXTURE_FETCH).expect("Could not insert memory barrier!");
+
+        program.bind();
+        program.try_bind_texture("computedTexture", &texture, 0).expect("Could not bind texture!");
+        handle.render(&quad, &program);
+    });
+}
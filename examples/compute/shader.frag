@@ -0,0 +1,10 @@
+#version 330 core
+
+uniform sampler2D computedTexture;
+
+in vec2 textureCoordinates;
+out vec4 FragColor;
+
+void main() {
+    FragColor = texture(computedTexture, textureCoordinates);
+}
@@ -0,0 +1,12 @@
+#version 330 core
+
+layout (location = 0) in vec3 lVertexPosition;
+layout (location = 2) in vec2 lVertexTextureCoordinates;
+
+out vec2 textureCoordinates;
+
+void main() {
+    textureCoordinates = lVertexTextureCoordinates;
+
+    gl_Position = vec4(lVertexPosition, 1.0);
+}
@@ -2,24 +2,15 @@ use std::f32::consts::TAU;
 
 use glam::{Vec2, Vec3};
 
-use bowl::data::buffer_mode::DrawMode;
-use bowl::data::Type;
-use bowl::data::vertex_array::VertexArray;
-use bowl::data::vertex_buffer::VertexBuffer;
-use bowl::data::vertex_buffer_layout::VertexBufferLayout;
-use bowl::gl_call;
-use bowl::renderable::Renderable;
+use bowl::renderable::{Mesh, PrimitiveTopology, Renderable};
 use bowl::vertex::Vertex;
 
 pub(crate) struct Circle {
-    vao: VertexArray,
-    sides: i32,
+    mesh: Mesh,
 }
 
 impl Circle {
     pub(crate) fn new(center: Vec2, radius: f32, sides: i32) -> Circle {
-        let mut vao = VertexArray::new();
-
         let mut vertices: Vec<Vertex> = Vec::new();
         let mut center_vertex = Vertex::from_position(Vec3::new(center.x, center.y, 0.0));
         center_vertex.color = Vec3::new(0.0, 0.0, 0.0);
@@ -38,24 +29,17 @@ impl Circle {
             vertices.push(vertex);
         }
 
-        let vb = VertexBuffer::new(vertices.as_slice(), DrawMode::STATIC);
-        let mut layout = VertexBufferLayout::new();
-        layout.add_attribute(Type::Float, 3);
-        layout.add_attribute(Type::Float, 3);
-        layout.add_attribute(Type::Float, 2);
-
-        vao.add_buffer(&vb, &layout);
+        let mut mesh = Mesh::new(vertices);
+        mesh.set_topology(PrimitiveTopology::TRIANGLE_FAN);
 
         return Circle {
-            vao,
-            sides,
+            mesh,
         };
     }
 }
 
 impl Renderable for Circle {
     fn render(&self) {
-        self.vao.bind();
-        gl_call!(gl::DrawArrays(gl::TRIANGLE_FAN, 0, self.sides + 2));
+        self.mesh.render();
     }
 }
@@ -20,10 +20,10 @@ fn main() {
 
     let circle = Circle::new(Vec2::new(0.0, 0.0), 0.8, 500);
 
-    let default_vert = Shader::new(VERTEX, include_str!("./shader.vert"));
-    let default_frag = Shader::new(FRAGMENT, include_str!("./shader.frag"));
+    let default_vert = Shader::new(VERTEX, include_str!("./shader.vert")).expect("Could not compile vertex shader!");
+    let default_frag = Shader::new(FRAGMENT, include_str!("./shader.frag")).expect("Could not compile fragment shader!");
 
-    let program = ShaderProgram::new(vec![default_vert, default_frag]);
+    let program = ShaderProgram::new(vec![default_vert, default_frag]).expect("Could not link shader program!");
 
 
     window.run(|handle| {
@@ -0,0 +1,56 @@
+use glam::{Vec2, Vec3, Vec4};
+
+use bowl::framebuffer::FramebufferBuilder;
+use bowl::renderable::Mesh;
+use bowl::shader::{Shader, ShaderProgram};
+use bowl::shader::ShaderType::{FRAGMENT, VERTEX};
+use bowl::texture::TextureFormat;
+use bowl::vertex::Vertex;
+
+fn main() {
+    let window = bowl::window::WindowBuilder::new()
+        .size(800, 600)
+        .title(String::from("Framebuffer Example"))
+        .create()
+        .expect("Could not create bowl window!");
+
+    let triangle = Mesh::new(vec![
+        Vertex::from(Vec3::new(-0.5, -0.5, 0.0), Vec3::new(1.0, 0.0, 0.0), Vec2::ZERO),
+        Vertex::from(Vec3::new(0.5, -0.5, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec2::ZERO),
+        Vertex::from(Vec3::new(0.0, 0.5, 0.0), Vec3::new(0.0, 0.0, 1.0), Vec2::ZERO),
+    ]);
+
+    let screen_quad = Mesh::new(vec![
+        Vertex::from(Vec3::new(-1.0, -1.0, 0.0), Vec3::ONE, Vec2::new(0.0, 0.0)),
+        Vertex::from(Vec3::new(1.0, -1.0, 0.0), Vec3::ONE, Vec2::new(1.0, 0.0)),
+        Vertex::from(Vec3::new(1.0, 1.0, 0.0), Vec3::ONE, Vec2::new(1.0, 1.0)),
+        Vertex::from(Vec3::new(-1.0, -1.0, 0.0), Vec3::ONE, Vec2::new(0.0, 0.0)),
+        Vertex::from(Vec3::new(1.0, 1.0, 0.0), Vec3::ONE, Vec2::new(1.0, 1.0)),
+        Vertex::from(Vec3::new(-1.0, 1.0, 0.0), Vec3::ONE, Vec2::new(0.0, 1.0)),
+    ]);
+
+    let scene_program = ShaderProgram::new(vec![
+        Shader::new(VERTEX, include_str!("./scene.vert")).expect("Could not compile vertex shader!"),
+        Shader::new(FRAGMENT, include_str!("./scene.frag")).expect("Could not compile fragment shader!"),
+    ]).expect("Could not link shader program!");
+
+    let screen_program = ShaderProgram::new(vec![
+        Shader::new(VERTEX, include_str!("./screen.vert")).expect("Could not compile vertex shader!"),
+        Shader::new(FRAGMENT, include_str!("./screen.frag")).expect("Could not compile fragment shader!"),
+    ]).expect("Could not link shader program!");
+
+    let framebuffer = FramebufferBuilder::new(400, 300)
+        .color_texture(TextureFormat::RGBA8)
+        .depth_renderbuffer(TextureFormat::DEPTH24_STENCIL8)
+        .create()
+        .expect("Could not create framebuffer!");
+
+    window.run(|handle| {
+        framebuffer.clear(Vec4::new(0.1, 0.1, 0.1, 1.0));
+        handle.render_to(&framebuffer, &triangle, &scene_program);
+
+        screen_program.bind();
+        screen_program.try_bind_texture("sceneTexture", framebuffer.color_texture(0).unwrap(), 0).expect("Could not bind scene texture!");
+        handle.render(&screen_quad, &screen_program);
+    });
+}
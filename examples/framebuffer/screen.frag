@@ -0,0 +1,11 @@
+#version 330 core
+
+uniform sampler2D sceneTexture;
+
+in vec2 textureCoordinates;
+out vec4 FragColor;
+
+void main() {
+    // Invert the colors of the rendered scene
+    FragColor = vec4(1.0 - texture(sceneTexture, textureCoordinates).rgb, 1.0);
+}
@@ -0,0 +1,39 @@
+use glam::Vec3;
+
+use bowl::renderable::Mesh;
+use bowl::shader::{Shader, ShaderProgram};
+use bowl::shader::ShaderType::{FRAGMENT, VERTEX};
+use bowl::vertex::Vertex;
+
+const FRAME_COUNT: u32 = 10;
+
+fn main() {
+    let mut window = bowl::window::WindowBuilder::new()
+        .size(320, 240)
+        .headless(true)
+        .create()
+        .expect("Could not create headless bowl window!");
+
+    let triangle = Mesh::new(vec![
+        Vertex::from_position(Vec3::new(-0.5, -0.5, 0.0)),
+        Vertex::from_position(Vec3::new(0.5, -0.5, 0.0)),
+        Vertex::from_position(Vec3::new(0.0, 0.5, 0.0)),
+    ]);
+
+    let program = ShaderProgram::new(vec![
+        Shader::new(VERTEX, include_str!("./random_triangles/shader.vert")).expect("Could not compile vertex shader!"),
+        Shader::new(FRAGMENT, include_str!("./random_triangles/shader.frag")).expect("Could not compile fragment shader!"),
+    ]).expect("Could not link shader program!");
+
+    // Headless windows are driven frame by frame instead of entering the blocking run loop
+    for frame in 0..FRAME_COUNT {
+        window.frame(|handle| {
+            handle.render(&triangle, &program);
+        });
+        println!("Rendered frame {}", frame);
+    }
+
+    let image = window.capture();
+    let center = image.pixel(image.width / 2, image.height / 2);
+    println!("Center pixel of the last frame: {:?}", center);
+}
@@ -0,0 +1,33 @@
+use glam::Vec3;
+
+use bowl::renderable::Mesh;
+use bowl::shader::ShaderProgram;
+use bowl::shader::ShaderType::{FRAGMENT, VERTEX};
+use bowl::vertex::Vertex;
+
+fn main() {
+    let window = bowl::window::WindowBuilder::new()
+        .size(800, 600)
+        .title(String::from("Shader Hot Reload Example"))
+        .create()
+        .expect("Could not create bowl window!");
+
+    let triangle = Mesh::new(vec![
+        Vertex::from_position(Vec3::new(-0.5, -0.5, 0.0)),
+        Vertex::from_position(Vec3::new(0.5, -0.5, 0.0)),
+        Vertex::from_position(Vec3::new(0.0, 0.5, 0.0)),
+    ]);
+
+    let program = ShaderProgram::from_files(&[
+        (VERTEX, concat!(env!("CARGO_MANIFEST_DIR"), "/examples/hot_reload/shader.vert")),
+        (FRAGMENT, concat!(env!("CARGO_MANIFEST_DIR"), "/examples/hot_reload/shader.frag")),
+    ]).expect("Could not load shader program!");
+
+    // Uniforms are set once and restored after every reload
+    program.bind();
+    program.set_uniform("triangleColor", Vec3::new(0.2, 0.6, 0.9)).expect("Could not set triangle color!");
+
+    window.run(|handle| {
+        handle.render(&triangle, &program);
+    });
+}
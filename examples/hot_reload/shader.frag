@@ -0,0 +1,10 @@
+#version 330 core
+
+out vec4 FragColor;
+
+uniform vec3 triangleColor;
+
+// Edit this file while the example is running to see the changes
+void main() {
+    FragColor = vec4(triangleColor, 1.0);
+}
@@ -0,0 +1,29 @@
+use bowl::window::input::{InputEvent, Key, MouseButton};
+
+fn main() {
+    let window = bowl::window::WindowBuilder::new()
+        .size(800, 600)
+        .title(String::from("Input Example"))
+        .create()
+        .expect("Could not initialize window");
+
+    window.run(|handle| {
+        let input = handle.input();
+
+        if input.is_key_pressed(Key::Space) {
+            println!("Space pressed");
+        }
+        if input.is_mouse_button_pressed(MouseButton::Button1) {
+            println!("Left click at {} (NDC: {})", input.cursor_position(), input.cursor_position_ndc());
+        }
+        if input.scroll_delta().y != 0.0 {
+            println!("Scrolled by {}", input.scroll_delta().y);
+        }
+
+        for event in input.events() {
+            if let InputEvent::Char(character) = event {
+                println!("Typed '{}'", character);
+            }
+        }
+    });
+}
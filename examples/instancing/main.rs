@@ -0,0 +1,73 @@
+use bytemuck::{Pod, Zeroable};
+use glam::{Mat4, Quat, Vec2, Vec3, Vec4};
+
+use bowl::data::buffer_mode::DrawMode;
+use bowl::data::instance_buffer::InstanceBuffer;
+use bowl::data::vertex_buffer_layout::VertexLayout;
+use bowl::renderable::InstancedMesh;
+use bowl::shader::{Shader, ShaderProgram};
+use bowl::shader::ShaderType::{FRAGMENT, VERTEX};
+use bowl::vertex::Vertex;
+
+const GRID_SIZE: usize = 100;
+
+/// Per-instance data, bound to the locations following the ones of `Vertex`.
+/// The color is a `Vec4`, as uploaded types must not contain padding.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, VertexLayout)]
+struct Instance {
+    transform: Mat4,
+    color: Vec4,
+}
+
+fn main() {
+    let window = bowl::window::WindowBuilder::new()
+        .size(800, 800)
+        .vsync(true)
+        .title(String::from("Instancing Example"))
+        .create()
+        .expect("Could not create bowl window!");
+
+    let quad_vertices = [
+        Vertex::from(Vec3::new(-0.5, -0.5, 0.0), Vec3::splat(1.0), Vec2::new(0.0, 0.0)),
+        Vertex::from(Vec3::new(0.5, -0.5, 0.0), Vec3::splat(1.0), Vec2::new(1.0, 0.0)),
+        Vertex::from(Vec3::new(0.5, 0.5, 0.0), Vec3::splat(1.0), Vec2::new(1.0, 1.0)),
+        Vertex::from(Vec3::new(-0.5, 0.5, 0.0), Vec3::splat(1.0), Vec2::new(0.0, 1.0)),
+    ];
+    let quad_indices: [u8; 6] = [0, 1, 2, 2, 3, 0];
+
+    // The instances are replaced every frame, so they are streamed through a persistently mapped ring buffer
+    let instance_buffer = InstanceBuffer::with_capacity(GRID_SIZE * GRID_SIZE, DrawMode::STREAM_PERSISTENT);
+    let mut quads = InstancedMesh::with_instance_buffer(&quad_vertices, instance_buffer);
+    quads.set_indices(&quad_indices);
+
+    let default_vert = Shader::new(VERTEX, include_str!("./shader.vert")).expect("Could not compile vertex shader!");
+    let default_frag = Shader::new(FRAGMENT, include_str!("./shader.frag")).expect("Could not compile fragment shader!");
+    let program = ShaderProgram::new(vec![default_vert, default_frag]).expect("Could not link shader program!");
+
+    let mut time: f32 = 0.0;
+    window.run(|handle| {
+        time += handle.dtime as f32 / 1_000_000.0;
+
+        // All 10000 quads are updated and drawn with a single draw call per frame
+        quads.set_instances(&instances(time));
+        handle.render(&quads, &program);
+    });
+}
+
+fn instances(time: f32) -> Vec<Instance> {
+    let cell_size = 2.0 / GRID_SIZE as f32;
+
+    (0..GRID_SIZE * GRID_SIZE)
+        .map(|i| {
+            let (x, y) = ((i % GRID_SIZE) as f32, (i / GRID_SIZE) as f32);
+            let position = Vec3::new(-1.0 + (x + 0.5) * cell_size, -1.0 + (y + 0.5) * cell_size, 0.0);
+            let rotation = Quat::from_rotation_z(time + (x + y) * 0.1);
+
+            Instance {
+                transform: Mat4::from_scale_rotation_translation(Vec3::splat(cell_size * 0.8), rotation, position),
+                color: Vec4::new(x / GRID_SIZE as f32, y / GRID_SIZE as f32, 0.5 + 0.5 * time.sin(), 1.0),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,17 @@
+#version 330 core
+
+layout (location = 0) in vec3 lVertexPosition;
+layout (location = 1) in vec3 lVertexColor;
+layout (location = 2) in vec2 lVertexTextureCoordinates;
+
+// Per-instance attributes, a mat4 occupies the locations 3 to 6
+layout (location = 3) in mat4 lInstanceTransform;
+layout (location = 7) in vec4 lInstanceColor;
+
+out vec3 vertexColor;
+
+void main() {
+    vertexColor = lVertexColor * lInstanceColor.rgb;
+
+    gl_Position = lInstanceTransform * vec4(lVertexPosition, 1.0);
+}
@@ -0,0 +1,44 @@
+use glam::Vec3;
+
+use bowl::renderable::Mesh;
+use bowl::shader::{Shader, ShaderProgram};
+use bowl::shader::ShaderType::{FRAGMENT, VERTEX};
+use bowl::vertex::Vertex;
+
+const FRAME_COUNT: u32 = 3;
+
+fn main() {
+    render();
+
+    // The objects of the scene were dropped before the window, so nothing was reported when the window was dropped
+    let leaks = bowl::leak_tracking::check();
+    assert_eq!(leaks, 0, "OpenGL objects were leaked");
+    println!("No OpenGL objects were leaked");
+}
+
+fn render() {
+    let mut window = bowl::window::WindowBuilder::new()
+        .size(320, 240)
+        .headless(true)
+        .create()
+        .expect("Could not create headless bowl window!");
+
+    let mut triangle = Mesh::new(vec![
+        Vertex::from_position(Vec3::new(-0.5, -0.5, 0.0)),
+        Vertex::from_position(Vec3::new(0.5, -0.5, 0.0)),
+        Vertex::from_position(Vec3::new(0.0, 0.5, 0.0)),
+    ]);
+    triangle.set_indices(&[0u16, 1, 2]);
+
+    let program = ShaderProgram::new(vec![
+        Shader::new(VERTEX, include_str!("./random_triangles/shader.vert")).expect("Could not compile vertex shader!"),
+        Shader::new(FRAGMENT, include_str!("./random_triangles/shader.frag")).expect("Could not compile fragment shader!"),
+    ]).expect("Could not link shader program!");
+
+    // Objects borrowed by the frame closure are still alive after the last frame, which is not a leak
+    for _ in 0..FRAME_COUNT {
+        window.frame(|handle| {
+            handle.render(&triangle, &program);
+        });
+    }
+}
@@ -18,10 +18,10 @@ fn main() {
     let mut mesh = Mesh::new(Vec::new());
 
 
-    let default_vert = Shader::new(VERTEX, include_str!("./shader.vert"));
-    let default_frag = Shader::new(FRAGMENT, include_str!("./shader.frag"));
+    let default_vert = Shader::new(VERTEX, include_str!("./shader.vert")).expect("Could not compile vertex shader!");
+    let default_frag = Shader::new(FRAGMENT, include_str!("./shader.frag")).expect("Could not compile fragment shader!");
 
-    let program = ShaderProgram::new(vec![default_vert, default_frag]);
+    let program = ShaderProgram::new(vec![default_vert, default_frag]).expect("Could not link shader program!");
 
     let triangle_spawn_rate = 2 * 1000 * 1000; // one triangle every 2 seconds
 
@@ -22,10 +22,10 @@ fn main() {
     ]);
 
 
-    let default_vert = Shader::new(VERTEX, include_str!("./shader.vert"));
-    let default_frag = Shader::new(FRAGMENT, include_str!("./shader.frag"));
+    let default_vert = Shader::new(VERTEX, include_str!("./shader.vert")).expect("Could not compile vertex shader!");
+    let default_frag = Shader::new(FRAGMENT, include_str!("./shader.frag")).expect("Could not compile fragment shader!");
 
-    let program = ShaderProgram::new(vec![default_vert, default_frag]);
+    let program = ShaderProgram::new(vec![default_vert, default_frag]).expect("Could not link shader program!");
 
 
     let mut sin_wave_generator = SinWaveGenerator::new();
@@ -34,7 +34,7 @@ fn main() {
         let current_brightness = sin_wave_generator.calc_current();
 
         program.bind();
-        program.set_uniform_float("triangleBrightness", vec![current_brightness]);
+        program.set_uniform("triangleBrightness", current_brightness).expect("Could not set triangle brightness!");
         handle.render(&triangle, &program);
     });
 }
\ No newline at end of file
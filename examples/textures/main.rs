@@ -1,9 +1,9 @@
 use glam::{Vec2, Vec3};
-use image::{GenericImageView, ImageFormat};
 
 use bowl::renderable::Mesh;
-use bowl::shader::{Shader, ShaderProgram, Texture};
+use bowl::shader::{Shader, ShaderProgram};
 use bowl::shader::ShaderType::{FRAGMENT, VERTEX};
+use bowl::texture::{SamplerDesc, Texture2D, WrapMode};
 use bowl::vertex::Vertex;
 
 fn main() {
@@ -18,36 +18,38 @@ fn main() {
         Vertex::from(
             Vec3::new(0.5, 0.5, 0.0),
             Vec3::splat(1.0),
-            Vec2::new(1.0, 0.0),
+            Vec2::new(1.0, 1.0),
         ),
         Vertex::from(
             Vec3::new(0.5, -0.5, 0.0),
             Vec3::splat(1.0),
-            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, 0.0),
         ),
         Vertex::from(
             Vec3::new(-0.5, -0.5, 0.0),
             Vec3::splat(1.0),
-            Vec2::new(0.0, 1.0),
+            Vec2::new(0.0, 0.0),
         ),
     ]);
 
 
-    let default_vert = Shader::new(VERTEX, include_str!("./shader.vert"));
-    let default_frag = Shader::new(FRAGMENT, include_str!("./shader.frag"));
-    let mut program = ShaderProgram::new(vec![default_vert, default_frag]);
-
+    let default_vert = Shader::new(VERTEX, include_str!("./shader.vert")).expect("Could not compile vertex shader!");
+    let default_frag = Shader::new(FRAGMENT, include_str!("./shader.frag")).expect("Could not compile fragment shader!");
+    let program = ShaderProgram::new(vec![default_vert, default_frag]).expect("Could not link shader program!");
 
-    let image = image::load_from_memory(include_bytes!("./awesomeface.png")).unwrap();
 
-    let mut texture = Texture::new("awesomefaceTexture", image.width(), image.height(), image.into_bytes());
+    let image = bowl::data::image::load_from_bytes(include_bytes!("./awesomeface.png")).expect("Could not load image!");
 
-    program.load_texture_2d_rgba(&mut texture);
+    let sampler = SamplerDesc::new()
+        .wrap(WrapMode::CLAMP_TO_EDGE)
+        .max_anisotropy(16.0);
+    let texture = Texture2D::with_sampler(image.width, image.height, image.format, &image.data, &sampler)
+        .expect("Could not create texture!");
 
     window.run(|handle| {
         program.bind();
 
-        program.set_uniform_texture(&texture);
+        program.try_bind_texture("awesomefaceTexture", &texture, 0).expect("Could not bind texture!");
 
         handle.render(&mesh, &program);
     });
@@ -1,7 +1,7 @@
 use glam::{Mat4, Vec3};
 
 use bowl::renderable::Mesh;
-use bowl::shader::{Shader, ShaderProgram, Texture};
+use bowl::shader::{Shader, ShaderProgram};
 use bowl::shader::ShaderType::{FRAGMENT, VERTEX};
 use bowl::vertex::Vertex;
 
@@ -19,29 +19,19 @@ fn main() {
     ]);
 
 
-    let default_vert = Shader::new(VERTEX, include_str!("./shader.vert"));
-    let default_frag = Shader::new(FRAGMENT, include_str!("./shader.frag"));
+    let default_vert = Shader::new(VERTEX, include_str!("./shader.vert")).expect("Could not compile vertex shader!");
+    let default_frag = Shader::new(FRAGMENT, include_str!("./shader.frag")).expect("Could not compile fragment shader!");
 
-    let program = ShaderProgram::new(vec![default_vert, default_frag]);
+    let program = ShaderProgram::new(vec![default_vert, default_frag]).expect("Could not link shader program!");
 
 
-    // TODO: MVP Matrix, Link with render3d function of WindowHandle
-    // let mut i = 0.0;
-    // let mut translation_vec = Vec3::new(0.0, 0.0, 0.0);
-    // let mut translation = Mat4::from_translation(translation_vec);
+    let mut i: f32 = 0.0;
     window.run(|handle| {
-        // i += handle.dtime as f32 / 1_000_000.;
+        i += handle.dtime as f32 / 1_000_000.;
 
-        program.bind();
+        let translation = Mat4::from_translation(Vec3::new(0.0, i.sin(), 0.0));
+        let rotation = Mat4::from_rotation_y(i);
 
-        // translation_vec.y = i.sin();
-
-        // translation = Mat4::from_translation(translation_vec);
-        // rotation = Mat4::from_rotation_z(i);
-        // let mvp = translation * rotation;
-
-        // program.set_uniform_mat4("transform", translation);
-
-        handle.render(&triangle, &program);
+        handle.render3d(&triangle, translation * rotation, &program);
     });
 }
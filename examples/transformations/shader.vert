@@ -2,8 +2,10 @@
 
 layout (location = 0) in vec3 aPos;
 
-uniform mat4 transform;
+uniform mat4 model;
+uniform mat4 view;
+uniform mat4 projection;
 
 void main() {
-    gl_Position = vec4(aPos.x, aPos.y, aPos.z, 1.0);
-}
\ No newline at end of file
+    gl_Position = projection * view * model * vec4(aPos, 1.0);
+}
@@ -0,0 +1,119 @@
+use glam::{Mat4, Vec3};
+
+use crate::shader::{ShaderProgram, UniformError};
+
+pub enum Projection {
+    Perspective {
+        /// Vertical field of view in radians
+        fov_y: f32,
+        near: f32,
+        far: f32,
+    },
+    Orthographic {
+        /// Visible height in world units, the visible width is derived from the aspect ratio
+        height: f32,
+        near: f32,
+        far: f32,
+    },
+}
+
+/// The names of the uniforms the model, view and projection matrices are uploaded to
+pub struct MvpUniformNames {
+    pub model: String,
+    pub view: String,
+    pub projection: String,
+}
+
+/// A camera providing the view and projection matrices for `WindowHandle::render3d`.
+///
+/// The aspect ratio of the window's camera is updated automatically whenever the framebuffer is resized.
+pub struct Camera {
+    pub position: Vec3,
+    pub target: Vec3,
+    pub up: Vec3,
+    pub projection: Projection,
+    pub uniform_names: MvpUniformNames,
+    aspect_ratio: f32,
+}
+
+impl Camera {
+    pub fn perspective(fov_y: f32, near: f32, far: f32) -> Self {
+        Self::new(Projection::Perspective { fov_y, near, far })
+    }
+
+    pub fn orthographic(height: f32, near: f32, far: f32) -> Self {
+        Self::new(Projection::Orthographic { height, near, far })
+    }
+
+    fn new(projection: Projection) -> Self {
+        Self {
+            position: Vec3::new(0.0, 0.0, 3.0),
+            target: Vec3::ZERO,
+            up: Vec3::Y,
+            projection,
+            uniform_names: MvpUniformNames::default(),
+            aspect_ratio: 1.0,
+        }
+    }
+
+    pub fn look_at(&mut self, position: Vec3, target: Vec3, up: Vec3) {
+        self.position = position;
+        self.target = target;
+        self.up = up;
+    }
+
+    pub fn aspect_ratio(&self) -> f32 {
+        self.aspect_ratio
+    }
+
+    pub fn set_aspect_ratio(&mut self, aspect_ratio: f32) {
+        self.aspect_ratio = aspect_ratio;
+    }
+
+    pub(crate) fn update_framebuffer_size(&mut self, width: i32, height: i32) {
+        // A minimized window has a framebuffer size of 0
+        if width > 0 && height > 0 {
+            self.aspect_ratio = width as f32 / height as f32;
+        }
+    }
+
+    pub fn view_matrix(&self) -> Mat4 {
+        Mat4::look_at_rh(self.position, self.target, self.up)
+    }
+
+    pub fn projection_matrix(&self) -> Mat4 {
+        match self.projection {
+            Projection::Perspective { fov_y, near, far } => {
+                Mat4::perspective_rh_gl(fov_y, self.aspect_ratio, near, far)
+            }
+            Projection::Orthographic { height, near, far } => {
+                let half_height = height / 2.0;
+                let half_width = half_height * self.aspect_ratio;
+                Mat4::orthographic_rh_gl(-half_width, half_width, -half_height, half_height, near, far)
+            }
+        }
+    }
+
+    /// Uploads the given model matrix and this camera's view and projection matrices to the bound shader program
+    pub fn upload_matrices(&self, model: Mat4, shader_program: &ShaderProgram) -> Result<(), UniformError> {
+        shader_program.set_uniform(&self.uniform_names.model, model)?;
+        shader_program.set_uniform(&self.uniform_names.view, self.view_matrix())?;
+        shader_program.set_uniform(&self.uniform_names.projection, self.projection_matrix())
+    }
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::perspective(45_f32.to_radians(), 0.1, 100.0)
+    }
+}
+
+impl Default for MvpUniformNames {
+    fn default() -> Self {
+        Self {
+            model: "model".to_string(),
+            view: "view".to_string(),
+            projection: "projection".to_string(),
+        }
+    }
+}
@@ -0,0 +1,364 @@
+use std::ffi::c_void;
+use std::marker::PhantomData;
+use std::mem::{replace, size_of};
+use std::ops::Range;
+use std::ptr::{copy_nonoverlapping, null};
+
+use gl::types::{GLbitfield, GLenum, GLintptr, GLsizeiptr, GLsync, GLuint};
+
+use crate::data::buffer_mode::{BufferMode, UpdateStrategy};
+use crate::gl_call;
+use crate::leak_tracking::{self, GlObjectKind};
+use crate::util;
+
+pub use bytemuck::{Pod, Zeroable};
+
+/// The binding point a buffer is used with, e.g. `ArrayBuffer` for vertex data
+pub trait BufferTarget {
+    const GL_ENUM: GLenum;
+}
+
+/// A binding point with indexed bindings, which are referenced by blocks in shaders
+pub trait IndexedBufferTarget: BufferTarget {}
+
+pub struct ArrayBuffer;
+pub struct ElementArrayBuffer;
+pub struct UniformBuffer;
+pub struct ShaderStorageBuffer;
+pub struct PixelPackBuffer;
+pub struct PixelUnpackBuffer;
+pub struct CopyReadBuffer;
+pub struct CopyWriteBuffer;
+
+/// A buffer of elements of type `T`, which is bound to the target `B`.
+///
+/// Elements must be plain old data, i.e. `Copy` types without padding, pointers or references, whose bytes can be
+/// uploaded as they are. Data is written and read through the copy targets, so updating a buffer does not change
+/// the bindings of the current vertex array.
+///
+/// How `set_data` replaces the contents is selected by the `BufferMode` the buffer is created with.
+pub struct Buffer<T: Pod, B: BufferTarget> {
+    opengl_id: GLuint,
+    usage: GLenum,
+    strategy: UpdateStrategy,
+    len: usize,
+    capacity: usize,
+    ring: Option<PersistentRing>,
+    _marker: PhantomData<(T, B)>,
+}
+
+/// The state of a buffer with the `PersistentRing` update strategy, whose storage holds `RING_SEGMENTS` segments
+/// of the buffer's capacity
+struct PersistentRing {
+    mapping: *mut c_void,
+    segment: usize,
+    /// Signaled once the draw calls using the corresponding segment are finished
+    fences: [GLsync; RING_SEGMENTS],
+}
+
+const RING_SEGMENTS: usize = 3;
+const RING_FLAGS: GLbitfield = gl::MAP_WRITE_BIT | gl::MAP_PERSISTENT_BIT | gl::MAP_COHERENT_BIT;
+
+impl<T: Pod, B: BufferTarget> Buffer<T, B> {
+    pub fn new(data: &[T], usage: impl BufferMode) -> Self {
+        let mut buffer = Self::with_capacity(data.len(), usage);
+        buffer.update_range(0, data);
+        buffer
+    }
+
+    /// Creates an empty buffer with storage for `capacity` elements
+    pub fn with_capacity(capacity: usize, usage: impl BufferMode) -> Self {
+        Self::create(capacity, usage, false)
+    }
+
+    /// Like `with_capacity`, for buffers of per-instance data, whose ring segments are selected with a base instance
+    pub(crate) fn with_instance_capacity(capacity: usize, usage: impl BufferMode) -> Self {
+        Self::create(capacity, usage, true)
+    }
+
+    fn create(capacity: usize, usage: impl BufferMode, base_instance: bool) -> Self {
+        let mut opengl_id: GLuint = 0;
+        gl_call!(gl::GenBuffers(1, &mut opengl_id));
+        leak_tracking::track_created(GlObjectKind::Buffer, opengl_id);
+
+        let mut strategy = usage.update_strategy();
+        // Segments are selected with a base vertex, a buffer range or, for instance data, a base instance
+        if strategy == UpdateStrategy::PersistentRing
+            && !(util::is_supported(4, 4, "GL_ARB_buffer_storage") && (!base_instance || util::is_supported(4, 2, "GL_ARB_base_instance"))) {
+            strategy = UpdateStrategy::Orphan;
+        }
+
+        let mut buffer = Self {
+            opengl_id,
+            usage: usage.to_gl_enum(),
+            strategy,
+            len: 0,
+            capacity,
+            ring: None,
+            _marker: PhantomData,
+        };
+
+        if strategy == UpdateStrategy::PersistentRing {
+            buffer.create_ring();
+        } else {
+            buffer.allocate(capacity);
+        }
+        buffer
+    }
+
+    /// Replaces all elements. The storage is only reallocated if the elements do not fit into it,
+    /// or if the buffer orphans its storage on every update.
+    pub fn set_data(&mut self, data: &[T]) {
+        if data.len() > self.capacity {
+            self.assert_growable(data.len());
+            self.allocate(data.len());
+            self.capacity = data.len();
+        } else {
+            match self.strategy {
+                UpdateStrategy::SubData => {}
+                UpdateStrategy::Orphan => self.allocate(self.capacity),
+                UpdateStrategy::PersistentRing => self.next_ring_segment(),
+            }
+        }
+
+        self.len = 0;
+        self.update_range(0, data);
+    }
+
+    /// Overwrites the elements starting at `offset` with `data`.
+    /// The range may extend past the current length, but not past the capacity.
+    pub fn update_range(&mut self, offset: usize, data: &[T]) {
+        assert!(offset + data.len() <= self.capacity,
+                "[Bowl] Could not update elements {}..{} of a buffer with a capacity of {}", offset, offset + data.len(), self.capacity);
+        self.len = self.len.max(offset + data.len());
+        if data.is_empty() {
+            return;
+        }
+
+        let offset = self.element_offset() + offset;
+        match &self.ring {
+            Some(ring) => unsafe {
+                // The mapping is coherent, so the written data is visible to the following draw calls
+                let bytes: &[u8] = bytemuck::cast_slice(data);
+                copy_nonoverlapping(bytes.as_ptr(), (ring.mapping as *mut u8).add(byte_size::<T>(offset)), bytes.len());
+            },
+            None => {
+                gl_call!(gl::BindBuffer(gl::COPY_WRITE_BUFFER, self.opengl_id));
+                gl_call!(gl::BufferSubData(gl::COPY_WRITE_BUFFER, byte_size::<T>(offset) as GLintptr, byte_size::<T>(data.len()) as GLsizeiptr, data.as_ptr() as *const c_void));
+            }
+        }
+    }
+
+    /// Appends the elements, growing the storage if necessary
+    pub fn extend_from_slice(&mut self, data: &[T]) {
+        self.reserve(data.len());
+        self.update_range(self.len, data);
+    }
+
+    /// Grows the storage to fit at least `additional` more elements, keeping the current contents.
+    /// The buffer keeps its OpenGL name, so vertex arrays referencing it stay valid.
+    pub fn reserve(&mut self, additional: usize) {
+        let required = self.len + additional;
+        if required <= self.capacity {
+            return;
+        }
+        self.assert_growable(required);
+
+        // Grow exponentially, so repeated appends only reallocate a logarithmic number of times
+        let capacity = required.max(self.capacity * 2);
+
+        if self.len == 0 {
+            self.allocate(capacity);
+        } else {
+            // Reallocating discards the contents, so they are kept in a temporary buffer in the meantime
+            let size = byte_size::<T>(self.len) as GLsizeiptr;
+            let mut temporary: GLuint = 0;
+            gl_call!(gl::GenBuffers(1, &mut temporary));
+            gl_call!(gl::BindBuffer(gl::COPY_WRITE_BUFFER, temporary));
+            gl_call!(gl::BufferData(gl::COPY_WRITE_BUFFER, size, null(), gl::STREAM_COPY));
+            gl_call!(gl::BindBuffer(gl::COPY_READ_BUFFER, self.opengl_id));
+            gl_call!(gl::CopyBufferSubData(gl::COPY_READ_BUFFER, gl::COPY_WRITE_BUFFER, 0, 0, size));
+
+            self.allocate(capacity);
+
+            gl_call!(gl::BindBuffer(gl::COPY_READ_BUFFER, temporary));
+            gl_call!(gl::BindBuffer(gl::COPY_WRITE_BUFFER, self.opengl_id));
+            gl_call!(gl::CopyBufferSubData(gl::COPY_READ_BUFFER, gl::COPY_WRITE_BUFFER, 0, 0, size));
+            gl_call!(gl::DeleteBuffers(1, &temporary));
+        }
+        self.capacity = capacity;
+    }
+
+    /// Copies `count` elements of `source`, starting at `source_offset`, to this buffer at `offset`
+    pub fn copy_from<S: BufferTarget>(&mut self, source: &Buffer<T, S>, source_offset: usize, offset: usize, count: usize) {
+        assert!(source_offset + count <= source.len,
+                "[Bowl] Could not copy elements {}..{} of a buffer with {} elements", source_offset, source_offset + count, source.len);
+        assert!(offset + count <= self.capacity,
+                "[Bowl] Could not copy to elements {}..{} of a buffer with a capacity of {}", offset, offset + count, self.capacity);
+        self.len = self.len.max(offset + count);
+        if count == 0 {
+            return;
+        }
+
+        let source_offset = source.element_offset() + source_offset;
+        let offset = self.element_offset() + offset;
+        gl_call!(gl::BindBuffer(gl::COPY_READ_BUFFER, source.opengl_id));
+        gl_call!(gl::BindBuffer(gl::COPY_WRITE_BUFFER, self.opengl_id));
+        gl_call!(gl::CopyBufferSubData(gl::COPY_READ_BUFFER, gl::COPY_WRITE_BUFFER,
+            byte_size::<T>(source_offset) as GLintptr, byte_size::<T>(offset) as GLintptr, byte_size::<T>(count) as GLsizeiptr));
+    }
+
+    /// Reads back all elements
+    pub fn read(&self) -> Vec<T> {
+        self.read_range(0..self.len)
+    }
+
+    /// Reads back the elements in the given range
+    pub fn read_range(&self, range: Range<usize>) -> Vec<T> {
+        assert!(range.start <= range.end && range.end <= self.len,
+                "[Bowl] Could not read elements {:?} of a buffer with {} elements", range, self.len);
+
+        let mut data = vec![T::zeroed(); range.end - range.start];
+        if !data.is_empty() {
+            let offset = self.element_offset() + range.start;
+            gl_call!(gl::BindBuffer(gl::COPY_READ_BUFFER, self.opengl_id));
+            gl_call!(gl::GetBufferSubData(gl::COPY_READ_BUFFER, byte_size::<T>(offset) as GLintptr, byte_size::<T>(data.len()) as GLsizeiptr, data.as_mut_ptr() as *mut c_void));
+        }
+        data
+    }
+
+    /// Removes all elements, keeping the storage
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    pub fn bind(&self) {
+        gl_call!(gl::BindBuffer(B::GL_ENUM, self.opengl_id));
+    }
+
+    /// The number of elements
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of elements that fit into the storage without reallocating it
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The index of the first element of the current contents in the storage. This is only non-zero for buffers
+    /// with the `PersistentRing` update strategy, whose draw calls must add it as base vertex or base instance.
+    pub fn element_offset(&self) -> usize {
+        self.ring.as_ref().map_or(0, |ring| ring.segment * self.capacity)
+    }
+
+    pub fn update_strategy(&self) -> UpdateStrategy {
+        self.strategy
+    }
+
+    pub fn opengl_id(&self) -> GLuint {
+        self.opengl_id
+    }
+
+    fn allocate(&self, capacity: usize) {
+        gl_call!(gl::BindBuffer(gl::COPY_WRITE_BUFFER, self.opengl_id));
+        gl_call!(gl::BufferData(gl::COPY_WRITE_BUFFER, byte_size::<T>(capacity) as GLsizeiptr, null(), self.usage));
+    }
+
+    fn create_ring(&mut self) {
+        // Immutable storage of size 0 is invalid
+        let size = byte_size::<T>(self.capacity * RING_SEGMENTS).max(1) as GLsizeiptr;
+
+        gl_call!(gl::BindBuffer(gl::COPY_WRITE_BUFFER, self.opengl_id));
+        gl_call!(gl::BufferStorage(gl::COPY_WRITE_BUFFER, size, null(), RING_FLAGS));
+        let mapping = gl_call!(gl::MapBufferRange(gl::COPY_WRITE_BUFFER, 0, size, RING_FLAGS));
+
+        self.ring = Some(PersistentRing {
+            mapping,
+            segment: 0,
+            fences: [null(); RING_SEGMENTS],
+        });
+    }
+
+    /// Switches to the next segment of the ring, waiting until the GPU finished the draw calls using it
+    fn next_ring_segment(&mut self) {
+        let Some(ring) = &mut self.ring else { return };
+
+        // All draw calls using the current segment have been issued at this point
+        ring.fences[ring.segment] = gl_call!(gl::FenceSync(gl::SYNC_GPU_COMMANDS_COMPLETE, 0));
+        ring.segment = (ring.segment + 1) % RING_SEGMENTS;
+
+        let fence = replace(&mut ring.fences[ring.segment], null());
+        if !fence.is_null() {
+            while gl_call!(gl::ClientWaitSync(fence, gl::SYNC_FLUSH_COMMANDS_BIT, 1_000_000_000)) == gl::TIMEOUT_EXPIRED {}
+            gl_call!(gl::DeleteSync(fence));
+        }
+    }
+
+    fn assert_growable(&self, required: usize) {
+        // The storage of ring buffers is immutable and can only be replaced with a new buffer name
+        assert!(self.ring.is_none(), "[Bowl] A persistent ring buffer with a capacity of {} can not grow to {} elements", self.capacity, required);
+    }
+
+    fn delete(&self) {
+        if let Some(ring) = &self.ring {
+            for fence in ring.fences.iter().filter(|fence| !fence.is_null()) {
+                gl_call!(gl::DeleteSync(*fence));
+            }
+        }
+
+        // Deleting a buffer also unmaps it
+        gl_call!(gl::DeleteBuffers(1, &self.opengl_id));
+        leak_tracking::track_deleted(GlObjectKind::Buffer, self.opengl_id);
+    }
+}
+
+impl<T: Pod, B: IndexedBufferTarget> Buffer<T, B> {
+    /// Binds this buffer to the given index of its target, e.g. the binding of a uniform block
+    pub fn bind_base(&self, index: u32) {
+        gl_call!(gl::BindBufferBase(B::GL_ENUM, index, self.opengl_id));
+    }
+
+    /// Binds the given range of elements of this buffer to the given index of its target
+    pub fn bind_range(&self, index: u32, range: Range<usize>) {
+        gl_call!(gl::BindBufferRange(B::GL_ENUM, index, self.opengl_id, byte_size::<T>(range.start) as GLintptr, byte_size::<T>(range.len()) as GLsizeiptr));
+    }
+}
+
+impl<T: Pod, B: BufferTarget> Drop for Buffer<T, B> {
+    fn drop(&mut self) {
+        self.delete();
+    }
+}
+
+fn byte_size<T>(count: usize) -> usize {
+    count * size_of::<T>()
+}
+
+macro_rules! impl_buffer_target {
+    ($($target:ident => $gl_enum:expr;)*) => {
+        $(
+            impl BufferTarget for $target {
+                const GL_ENUM: GLenum = $gl_enum;
+            }
+        )*
+    };
+}
+
+impl_buffer_target! {
+    ArrayBuffer => gl::ARRAY_BUFFER;
+    ElementArrayBuffer => gl::ELEMENT_ARRAY_BUFFER;
+    UniformBuffer => gl::UNIFORM_BUFFER;
+    ShaderStorageBuffer => gl::SHADER_STORAGE_BUFFER;
+    PixelPackBuffer => gl::PIXEL_PACK_BUFFER;
+    PixelUnpackBuffer => gl::PIXEL_UNPACK_BUFFER;
+    CopyReadBuffer => gl::COPY_READ_BUFFER;
+    CopyWriteBuffer => gl::COPY_WRITE_BUFFER;
+}
+
+impl IndexedBufferTarget for UniformBuffer {}
+impl IndexedBufferTarget for ShaderStorageBuffer {}
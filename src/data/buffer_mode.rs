@@ -2,20 +2,49 @@ use gl::types::GLenum;
 
 pub trait BufferMode {
     fn to_gl_enum(&self) -> GLenum;
+
+    /// How `Buffer::set_data` replaces the contents of a buffer that are still used by previous draw calls
+    fn update_strategy(&self) -> UpdateStrategy {
+        UpdateStrategy::SubData
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UpdateStrategy {
+    /// Overwrites the contents in place, which waits for previous draw calls using them
+    SubData,
+    /// Reallocates the storage before writing, so previous draw calls keep using the old storage
+    Orphan,
+    /// Writes into the next segment of a persistently mapped storage, which is only reused after three updates.
+    /// Requires OpenGL 4.4 or `ARB_buffer_storage`, and for instance buffers also OpenGL 4.2 or `ARB_base_instance`,
+    /// otherwise `Orphan` is used.
+    PersistentRing,
 }
 
+#[allow(non_camel_case_types)]
 pub enum DrawMode {
     STREAM,
     STATIC,
     DYNAMIC,
+    /// Streaming data that is replaced every frame, using a persistently mapped ring buffer if supported.
+    /// Such buffers can not grow beyond the capacity they are created with.
+    STREAM_PERSISTENT,
 }
 
 impl BufferMode for DrawMode {
     fn to_gl_enum(&self) -> GLenum {
         match self {
-            DrawMode::STREAM => gl::STREAM_DRAW,
+            DrawMode::STREAM | DrawMode::STREAM_PERSISTENT => gl::STREAM_DRAW,
             DrawMode::STATIC => gl::STATIC_DRAW,
             DrawMode::DYNAMIC => gl::DYNAMIC_DRAW,
         }
     }
+
+    fn update_strategy(&self) -> UpdateStrategy {
+        match self {
+            DrawMode::STREAM => UpdateStrategy::Orphan,
+            DrawMode::STATIC | DrawMode::DYNAMIC => UpdateStrategy::SubData,
+            DrawMode::STREAM_PERSISTENT => UpdateStrategy::PersistentRing,
+        }
+    }
 }
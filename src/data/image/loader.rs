@@ -0,0 +1,110 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+use image::DynamicImage;
+
+use crate::data::image::RgbaImage;
+use crate::texture::TextureFormat;
+
+/// Decoded pixel data, ready to be uploaded to a texture.
+///
+/// Rows are stored bottom to top, matching the origin of OpenGL texture coordinates in the bottom left corner.
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub format: TextureFormat,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct ImageError(image::ImageError);
+
+/// Loads and decodes the image file at the given path, the file format is guessed from the extension and content
+pub fn load_from_path<P: AsRef<Path>>(path: P) -> Result<Image, ImageError> {
+    let image = image::io::Reader::open(path)
+        .map_err(|error| ImageError(image::ImageError::IoError(error)))?
+        .with_guessed_format()
+        .map_err(|error| ImageError(image::ImageError::IoError(error)))?
+        .decode()
+        .map_err(ImageError)?;
+
+    Ok(Image::from_dynamic_image(image))
+}
+
+/// Decodes an image file from memory, e.g. embedded with `include_bytes!`, the file format is guessed from the content
+pub fn load_from_bytes(bytes: &[u8]) -> Result<Image, ImageError> {
+    let image = image::load_from_memory(bytes).map_err(ImageError)?;
+
+    Ok(Image::from_dynamic_image(image))
+}
+
+impl Image {
+    /// Converts a decoded image into the texture format matching its channels.
+    /// 8-bit color images keep their channel count, grayscale images are expanded to RGB or RGBA, so they sample
+    /// as gray instead of red in shaders. HDR and 16-bit images are converted to 32-bit floats.
+    fn from_dynamic_image(image: DynamicImage) -> Self {
+        // Image files store their rows top to bottom
+        let image = image.flipv();
+        let (width, height) = (image.width(), image.height());
+
+        let (format, data) = match image {
+            DynamicImage::ImageLuma8(_) => (TextureFormat::RGB8, image.into_rgb8().into_raw()),
+            DynamicImage::ImageLumaA8(_) => (TextureFormat::RGBA8, image.into_rgba8().into_raw()),
+            DynamicImage::ImageRgb8(image) => (TextureFormat::RGB8, image.into_raw()),
+            DynamicImage::ImageRgba8(image) => (TextureFormat::RGBA8, image.into_raw()),
+            DynamicImage::ImageRgb32F(image) => (TextureFormat::RGB32F, floats_to_bytes(image.into_raw())),
+            DynamicImage::ImageRgb16(_) => (TextureFormat::RGB32F, floats_to_bytes(image.into_rgb32f().into_raw())),
+            image => (TextureFormat::RGBA32F, floats_to_bytes(image.into_rgba32f().into_raw())),
+        };
+
+        Self {
+            width,
+            height,
+            format,
+            data,
+        }
+    }
+
+    /// Flips the rows of the image, e.g. to restore the top to bottom order of the image file
+    pub fn flip_vertically(&mut self) {
+        let row_length = self.width as usize * self.format.bytes_per_pixel();
+        let rows = self.height as usize;
+
+        for row in 0..rows / 2 {
+            let (top, bottom) = self.data.split_at_mut((rows - row - 1) * row_length);
+            top[row * row_length..(row + 1) * row_length].swap_with_slice(&mut bottom[..row_length]);
+        }
+    }
+}
+
+pub(crate) fn load_rgba(path: &Path) -> Result<RgbaImage, ImageError> {
+    let image = image::open(path).map_err(ImageError)?.into_rgba8();
+
+    Ok(RgbaImage {
+        width: image.width(),
+        height: image.height(),
+        data: image.into_raw(),
+    })
+}
+
+pub(crate) fn save_png(path: &Path, width: u32, height: u32, rgba: &[u8]) -> Result<(), ImageError> {
+    image::save_buffer_with_format(path, rgba, width, height, image::ColorType::Rgba8, image::ImageFormat::Png)
+        .map_err(ImageError)
+}
+
+fn floats_to_bytes(floats: Vec<f32>) -> Vec<u8> {
+    floats.iter().flat_map(|float| float.to_ne_bytes()).collect()
+}
+
+impl Display for ImageError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[Bowl] Could not load or save image: {}", self.0)
+    }
+}
+
+impl Error for ImageError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+    }
+}
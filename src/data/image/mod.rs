@@ -0,0 +1,49 @@
+//! Image data read back from framebuffers and, with the `image` feature, loading of PNG, JPEG, BMP, TGA and HDR files.
+
+#[cfg(feature = "image")]
+use std::path::Path;
+
+#[cfg(feature = "image")]
+pub use loader::{Image, ImageError, load_from_bytes, load_from_path};
+
+#[cfg(feature = "image")]
+mod loader;
+
+/// 8-bit RGBA pixels with rows stored top to bottom, as in image files
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RgbaImage {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+impl RgbaImage {
+    /// Creates an image from rows stored bottom to top, as returned by OpenGL
+    pub(crate) fn from_bottom_to_top_rows(width: u32, height: u32, data: Vec<u8>) -> Self {
+        let row_length = width as usize * 4;
+        let data = data.chunks_exact(row_length).rev().flatten().copied().collect();
+
+        Self {
+            width,
+            height,
+            data,
+        }
+    }
+
+    /// The pixel at the given position, relative to the top left corner
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let index = (y as usize * self.width as usize + x as usize) * 4;
+        [self.data[index], self.data[index + 1], self.data[index + 2], self.data[index + 3]]
+    }
+
+    /// Loads a PNG file without flipping its rows, e.g. a previously saved screenshot
+    #[cfg(feature = "image")]
+    pub fn load_png<P: AsRef<Path>>(path: P) -> Result<Self, ImageError> {
+        loader::load_rgba(path.as_ref())
+    }
+
+    #[cfg(feature = "image")]
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<(), ImageError> {
+        loader::save_png(path.as_ref(), self.width, self.height, &self.data)
+    }
+}
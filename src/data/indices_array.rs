@@ -1,42 +1,104 @@
-use std::ffi::c_void;
-use std::mem::size_of_val;
+use gl::types::GLenum;
 
-use gl::types::{GLenum, GLsizeiptr};
+use crate::data::buffer::{Buffer, ElementArrayBuffer, Pod};
 use crate::data::buffer_mode::BufferMode;
 
+/// The type of the indices of an `IndicesBuffer`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndexType {
+    UnsignedByte,
+    UnsignedShort,
+    UnsignedInt,
+}
+
+/// An integer type that can be stored in an `IndicesBuffer`
+pub trait Index: Pod {
+    const TYPE: IndexType;
+}
 
+/// A buffer of indices into the vertices of a vertex array, whose type may change when the data is replaced
 pub struct IndicesBuffer {
-    opengl_id: u32,
-    usage: GLenum,
+    buffer: Buffer<u8, ElementArrayBuffer>,
+    index_type: IndexType,
+    len: usize,
 }
 
-impl IndicesBuffer {
-    pub fn new(data: &[u32], usage: impl BufferMode) -> Self {
-        let usage = usage.to_gl_enum();
-
-        let mut buffer: u32 = 0;
-        unsafe {
-            gl::GenBuffers(1, &mut buffer);
-            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, buffer);
-            gl::BufferData(gl::ELEMENT_ARRAY_BUFFER, size_of_val(data) as GLsizeiptr, data.as_ptr() as *mut c_void, usage);
+impl IndexType {
+    pub fn to_gl_enum(&self) -> GLenum {
+        match self {
+            IndexType::UnsignedByte => gl::UNSIGNED_BYTE,
+            IndexType::UnsignedShort => gl::UNSIGNED_SHORT,
+            IndexType::UnsignedInt => gl::UNSIGNED_INT,
         }
+    }
 
-        IndicesBuffer {
-            opengl_id: buffer,
-            usage,
+    pub fn size(&self) -> usize {
+        match self {
+            IndexType::UnsignedByte => 1,
+            IndexType::UnsignedShort => 2,
+            IndexType::UnsignedInt => 4,
         }
     }
 
-    pub fn set_data(&self, data: &[u32]) {
-        self.bind();
-        unsafe {
-            gl::BufferData(gl::ELEMENT_ARRAY_BUFFER, size_of_val(data) as GLsizeiptr, data.as_ptr() as *mut c_void, self.usage);
+    /// The largest index of this type, which is used as primitive restart index
+    pub fn max_value(&self) -> u32 {
+        match self {
+            IndexType::UnsignedByte => u8::MAX as u32,
+            IndexType::UnsignedShort => u16::MAX as u32,
+            IndexType::UnsignedInt => u32::MAX,
         }
     }
+}
 
-    pub fn bind(&self) {
-        unsafe {
-            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.opengl_id);
+impl Index for u8 {
+    const TYPE: IndexType = IndexType::UnsignedByte;
+}
+
+impl Index for u16 {
+    const TYPE: IndexType = IndexType::UnsignedShort;
+}
+
+impl Index for u32 {
+    const TYPE: IndexType = IndexType::UnsignedInt;
+}
+
+impl IndicesBuffer {
+    pub fn new<I: Index>(data: &[I], usage: impl BufferMode) -> Self {
+        IndicesBuffer {
+            buffer: Buffer::new(bytemuck::cast_slice(data), usage),
+            index_type: I::TYPE,
+            len: data.len(),
         }
     }
-}
\ No newline at end of file
+
+    /// Replaces all indices, which may be of a different type than before
+    pub fn set_data<I: Index>(&mut self, data: &[I]) {
+        self.buffer.set_data(bytemuck::cast_slice(data));
+        self.index_type = I::TYPE;
+        self.len = data.len();
+    }
+
+    pub fn bind(&self) {
+        self.buffer.bind();
+    }
+
+    /// The offset of the current indices in the buffer in bytes. This is only non-zero for buffers with the
+    /// `PersistentRing` update strategy, whose draw calls must add it to the offset of the first index.
+    pub fn byte_offset(&self) -> usize {
+        // The buffer stores bytes, so its element offset is a byte offset
+        self.buffer.element_offset()
+    }
+
+    pub fn index_type(&self) -> IndexType {
+        self.index_type
+    }
+
+    /// The number of indices
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
@@ -0,0 +1,60 @@
+use crate::data::buffer::Pod;
+use crate::data::buffer_mode::{BufferMode, DrawMode};
+use crate::data::vertex_buffer::VertexBuffer;
+use crate::data::vertex_buffer_layout::{VertexBufferLayout, VertexLayout};
+
+/// A vertex buffer of per-instance data, e.g. transforms or colors, for instanced draw calls.
+///
+/// Add it to a vertex array with `VertexArray::add_instance_buffer`, so its attributes advance once per instance.
+pub struct InstanceBuffer<T: VertexLayout + Pod> {
+    buffer: VertexBuffer<T>,
+}
+
+impl<T: VertexLayout + Pod> InstanceBuffer<T> {
+    pub fn new(instances: &[T], usage: impl BufferMode) -> Self {
+        let mut instance_buffer = Self::with_capacity(instances.len(), usage);
+        instance_buffer.buffer.update_range(0, instances);
+        instance_buffer
+    }
+
+    /// Creates an empty instance buffer, meant to be updated every frame with `set_data`
+    pub fn streaming() -> Self {
+        Self::new(&[], DrawMode::STREAM)
+    }
+
+    /// Creates an empty instance buffer with storage for `capacity` instances, e.g. with `DrawMode::STREAM_PERSISTENT`
+    pub fn with_capacity(capacity: usize, usage: impl BufferMode) -> Self {
+        Self {
+            buffer: VertexBuffer::with_instance_capacity(capacity, usage),
+        }
+    }
+
+    /// Replaces all instances. The storage is only reallocated if the instances do not fit into it.
+    pub fn set_data(&mut self, instances: &[T]) {
+        self.buffer.set_data(instances);
+    }
+
+    /// The layout of the instance type with a divisor of 1
+    pub fn layout() -> VertexBufferLayout {
+        let mut layout = T::layout();
+        layout.set_divisor(1);
+        layout
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// The index of the first current instance in the storage, see `Buffer::element_offset`
+    pub fn element_offset(&self) -> usize {
+        self.buffer.element_offset()
+    }
+
+    pub fn vertex_buffer(&self) -> &VertexBuffer<T> {
+        &self.buffer
+    }
+}
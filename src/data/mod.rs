@@ -1,21 +1,69 @@
 use gl::types::GLenum;
 
+pub mod buffer;
 pub mod vertex_buffer;
+pub mod instance_buffer;
 pub mod vertex_array;
 pub mod indices_array;
+pub mod uniform_buffer;
+pub mod std140;
 pub mod vertex_buffer_layout;
 pub mod buffer_mode;
 pub mod image;
 
-#[derive(Clone, Copy)]
+/// The type of the components of a vertex attribute
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Type {
     Float,
+    /// 16-bit float, the data is passed as its raw bits
+    HalfFloat,
+    Byte,
+    UnsignedByte,
+    Short,
+    UnsignedShort,
+    Int,
+    UnsignedInt,
+    /// Four components packed into 32 bits: 10 bits each for x, y and z, 2 bits for w, as signed integers
+    Int2_10_10_10Rev,
+    /// Four components packed into 32 bits: 10 bits each for x, y and z, 2 bits for w, as unsigned integers
+    UnsignedInt2_10_10_10Rev,
 }
 
 impl Type {
+    /// The OpenGL type and the size of a single component in bytes.
+    /// For packed types, the size is the size of all four components together.
     pub fn to_gl_enum_and_size(&self) -> (GLenum, u32) {
         match self {
             Type::Float => (gl::FLOAT, 4),
+            Type::HalfFloat => (gl::HALF_FLOAT, 2),
+            Type::Byte => (gl::BYTE, 1),
+            Type::UnsignedByte => (gl::UNSIGNED_BYTE, 1),
+            Type::Short => (gl::SHORT, 2),
+            Type::UnsignedShort => (gl::UNSIGNED_SHORT, 2),
+            Type::Int => (gl::INT, 4),
+            Type::UnsignedInt => (gl::UNSIGNED_INT, 4),
+            Type::Int2_10_10_10Rev => (gl::INT_2_10_10_10_REV, 4),
+            Type::UnsignedInt2_10_10_10Rev => (gl::UNSIGNED_INT_2_10_10_10_REV, 4),
         }
     }
+
+    /// The size in bytes of an attribute with `count` components of this type
+    pub fn attribute_size(&self, count: u32) -> u32 {
+        if self.is_packed() {
+            self.to_gl_enum_and_size().1
+        } else {
+            count * self.to_gl_enum_and_size().1
+        }
+    }
+
+    /// Whether the components are integers, which are either read as integers in the shader or normalized to floats
+    pub fn is_integer(&self) -> bool {
+        !matches!(self, Type::Float | Type::HalfFloat | Type::Int2_10_10_10Rev | Type::UnsignedInt2_10_10_10Rev)
+    }
+
+    /// Whether all components are packed into a single 32-bit value, which requires a count of 4
+    pub fn is_packed(&self) -> bool {
+        matches!(self, Type::Int2_10_10_10Rev | Type::UnsignedInt2_10_10_10Rev)
+    }
 }
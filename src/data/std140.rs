@@ -0,0 +1,185 @@
+use glam::{DVec2, DVec3, DVec4, IVec2, IVec3, IVec4, Mat2, Mat3, Mat4, UVec2, UVec3, UVec4, Vec2, Vec3, Vec4};
+
+pub use bowl_derive::Std140;
+
+/// A type that can be written to a uniform buffer with the std140 layout of uniform blocks.
+///
+/// Usually derived with `#[derive(Std140)]`, which lays out the fields in declaration order, inserting the padding
+/// required by std140, e.g. after `vec3` members or between elements of arrays.
+pub trait Std140 {
+    /// The base alignment in bytes
+    const ALIGN: usize;
+    /// The size in bytes, including the padding of arrays and structs
+    const SIZE: usize;
+
+    /// Writes this value to the given buffer, which has a length of `SIZE`
+    fn write_std140(&self, buffer: &mut [u8]);
+
+    /// This value in std140 layout
+    fn to_std140_bytes(&self) -> Vec<u8> {
+        let mut buffer = vec![0; Self::SIZE];
+        self.write_std140(&mut buffer);
+        buffer
+    }
+}
+
+/// Rounds `offset` up to the next multiple of `align`
+pub const fn align_to(offset: usize, align: usize) -> usize {
+    offset.div_ceil(align) * align
+}
+
+// Used by the code generated by the Std140 derive, as `Ord::max` is not const
+#[doc(hidden)]
+pub const fn max(a: usize, b: usize) -> usize {
+    if a > b { a } else { b }
+}
+
+/// Implements `Std140` for scalars and vectors, whose std140 layout matches their Rust layout
+macro_rules! impl_std140 {
+    ($($rust_type:ty => $align:literal, $size:literal;)*) => {$(
+        impl Std140 for $rust_type {
+            const ALIGN: usize = $align;
+            const SIZE: usize = $size;
+
+            fn write_std140(&self, buffer: &mut [u8]) {
+                buffer.copy_from_slice(bytemuck::bytes_of(self));
+            }
+        }
+    )*};
+}
+
+impl_std140! {
+    f32 => 4, 4;
+    i32 => 4, 4;
+    u32 => 4, 4;
+    f64 => 8, 8;
+    Vec2 => 8, 8;
+    IVec2 => 8, 8;
+    UVec2 => 8, 8;
+    DVec2 => 16, 16;
+    Vec3 => 16, 12;
+    IVec3 => 16, 12;
+    UVec3 => 16, 12;
+    DVec3 => 32, 24;
+    Vec4 => 16, 16;
+    IVec4 => 16, 16;
+    UVec4 => 16, 16;
+    DVec4 => 32, 32;
+}
+
+impl Std140 for bool {
+    const ALIGN: usize = 4;
+    const SIZE: usize = 4;
+
+    fn write_std140(&self, buffer: &mut [u8]) {
+        u32::from(*self).write_std140(buffer);
+    }
+}
+
+/// Implements `Std140` for matrices, which are laid out like arrays of their column vectors
+macro_rules! impl_std140_matrix {
+    ($($rust_type:ty => $column:ty, $columns:literal;)*) => {$(
+        impl Std140 for $rust_type {
+            const ALIGN: usize = <[$column; $columns]>::ALIGN;
+            const SIZE: usize = <[$column; $columns]>::SIZE;
+
+            fn write_std140(&self, buffer: &mut [u8]) {
+                let columns: [$column; $columns] = bytemuck::cast(*self);
+                columns.write_std140(buffer);
+            }
+        }
+    )*};
+}
+
+impl_std140_matrix! {
+    Mat2 => Vec2, 2;
+    Mat3 => Vec3, 3;
+    Mat4 => Vec4, 4;
+}
+
+/// Array elements are aligned like vec4, so every element starts at a multiple of 16 bytes
+impl<T: Std140, const N: usize> Std140 for [T; N] {
+    const ALIGN: usize = align_to(T::ALIGN, 16);
+    const SIZE: usize = N * align_to(T::SIZE, Self::ALIGN);
+
+    fn write_std140(&self, buffer: &mut [u8]) {
+        let stride = align_to(T::SIZE, Self::ALIGN);
+        for (element, buffer) in self.iter().zip(buffer.chunks_exact_mut(stride)) {
+            element.write_std140(&mut buffer[..T::SIZE]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Std140)]
+    struct Light {
+        position: Vec3,
+        intensity: f32,
+        color: Vec3,
+    }
+
+    #[derive(Std140)]
+    struct Scene {
+        normal: Mat3,
+        weights: [f32; 3],
+        light: Light,
+        count: u32,
+        direction: Vec3,
+        enabled: bool,
+    }
+
+    fn write_f32s(buffer: &mut [u8], offset: usize, values: &[f32]) {
+        buffer[offset..offset + 4 * values.len()].copy_from_slice(bytemuck::cast_slice(values));
+    }
+
+    #[test]
+    fn sizes_and_alignments() {
+        assert_eq!((f32::ALIGN, f32::SIZE), (4, 4));
+        assert_eq!((Vec2::ALIGN, Vec2::SIZE), (8, 8));
+        assert_eq!((Vec3::ALIGN, Vec3::SIZE), (16, 12));
+        assert_eq!((DVec3::ALIGN, DVec3::SIZE), (32, 24));
+        assert_eq!((<[f32; 3]>::ALIGN, <[f32; 3]>::SIZE), (16, 48));
+        assert_eq!((<[Vec3; 2]>::ALIGN, <[Vec3; 2]>::SIZE), (16, 32));
+        assert_eq!((Mat2::ALIGN, Mat2::SIZE), (16, 32));
+        assert_eq!((Mat3::ALIGN, Mat3::SIZE), (16, 48));
+        assert_eq!((Mat4::ALIGN, Mat4::SIZE), (16, 64));
+        // position 0..12, intensity 12..16, color 16..28, padded to the struct alignment
+        assert_eq!((Light::ALIGN, Light::SIZE), (16, 32));
+        // normal 0..48, weights 48..96, light 96..128, count 128..132, direction 144..156, enabled 156..160
+        assert_eq!((Scene::ALIGN, Scene::SIZE), (16, 160));
+    }
+
+    #[test]
+    fn derived_struct_bytes() {
+        let scene = Scene {
+            normal: Mat3::from_cols(Vec3::new(1.0, 2.0, 3.0), Vec3::new(4.0, 5.0, 6.0), Vec3::new(7.0, 8.0, 9.0)),
+            weights: [0.25, 0.5, 0.75],
+            light: Light {
+                position: Vec3::new(10.0, 11.0, 12.0),
+                intensity: 13.0,
+                color: Vec3::new(14.0, 15.0, 16.0),
+            },
+            count: 17,
+            direction: Vec3::new(18.0, 19.0, 20.0),
+            enabled: true,
+        };
+
+        let mut expected = vec![0; 160];
+        write_f32s(&mut expected, 0, &[1.0, 2.0, 3.0]);
+        write_f32s(&mut expected, 16, &[4.0, 5.0, 6.0]);
+        write_f32s(&mut expected, 32, &[7.0, 8.0, 9.0]);
+        write_f32s(&mut expected, 48, &[0.25]);
+        write_f32s(&mut expected, 64, &[0.5]);
+        write_f32s(&mut expected, 80, &[0.75]);
+        write_f32s(&mut expected, 96, &[10.0, 11.0, 12.0, 13.0]);
+        write_f32s(&mut expected, 112, &[14.0, 15.0, 16.0]);
+        expected[128..132].copy_from_slice(&17u32.to_ne_bytes());
+        write_f32s(&mut expected, 144, &[18.0, 19.0, 20.0]);
+        expected[156..160].copy_from_slice(&1u32.to_ne_bytes());
+
+        assert_eq!(scene.to_std140_bytes(), expected);
+    }
+}
@@ -0,0 +1,61 @@
+use std::marker::PhantomData;
+
+use gl::types::GLint;
+
+use crate::data::buffer::{self, Buffer};
+use crate::data::buffer_mode::BufferMode;
+use crate::data::std140::{align_to, Std140};
+use crate::gl_call;
+
+/// A buffer holding a single value in std140 layout, which is read by uniform blocks of shader programs.
+/// The blocks must be declared with `layout(std140)`.
+///
+/// The buffer is bound to a binding point, which can be shared by the uniform blocks of any number of programs,
+/// see `ShaderProgram::bind_uniform_block`. Per-frame data like camera matrices only has to be set once for all programs.
+pub struct UniformBuffer<T: Std140> {
+    buffer: Buffer<u8, buffer::UniformBuffer>,
+    binding: u32,
+    /// The std140 bytes of the current value, reused for every update
+    data: Vec<u8>,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Std140> UniformBuffer<T> {
+    /// Creates a uniform buffer holding `value` and binds it to the given binding point
+    pub fn new(binding: u32, value: &T, usage: impl BufferMode) -> Self {
+        // Ring buffers store multiple values, each of which must start at a multiple of the offset alignment
+        let mut offset_alignment: GLint = 0;
+        gl_call!(gl::GetIntegerv(gl::UNIFORM_BUFFER_OFFSET_ALIGNMENT, &mut offset_alignment));
+        let capacity = align_to(T::SIZE, offset_alignment.max(1) as usize);
+
+        let mut buffer = Self {
+            buffer: Buffer::with_capacity(capacity, usage),
+            binding,
+            data: vec![0; T::SIZE],
+            _marker: PhantomData,
+        };
+        buffer.set(value);
+        buffer
+    }
+
+    /// Replaces the value and binds the buffer to its binding point again, as the value may be stored at a different offset
+    pub fn set(&mut self, value: &T) {
+        value.write_std140(&mut self.data);
+        self.buffer.set_data(&self.data);
+        self.bind();
+    }
+
+    /// Binds the current value to the binding point of this buffer, e.g. after another buffer was bound to it
+    pub fn bind(&self) {
+        let offset = self.buffer.element_offset();
+        self.buffer.bind_range(self.binding, offset..offset + T::SIZE);
+    }
+
+    pub fn binding(&self) -> u32 {
+        self.binding
+    }
+
+    pub fn buffer(&self) -> &Buffer<u8, buffer::UniformBuffer> {
+        &self.buffer
+    }
+}
@@ -1,12 +1,22 @@
+use std::collections::BTreeSet;
 use std::ffi::c_void;
 
-use gl::types::GLsizei;
+use gl::types::{GLboolean, GLint, GLsizei, GLuint};
 
+use crate::data::buffer::Pod;
+use crate::data::instance_buffer::InstanceBuffer;
 use crate::data::vertex_buffer::VertexBuffer;
-use crate::data::vertex_buffer_layout::VertexBufferLayout;
+use crate::data::vertex_buffer_layout::{VertexBufferLayout, VertexLayout};
+use crate::gl_call;
+use crate::leak_tracking::{self, GlObjectKind};
 
+/// A vertex array object, which combines the attributes of one or more vertex buffers.
+///
+/// Attributes without an explicit location are assigned the next free location, in the order they were added,
+/// so separate buffers, e.g. for positions and colors, can be added after each other.
 pub struct VertexArray {
     opengl_id: u32,
+    used_locations: BTreeSet<GLuint>,
 }
 
 impl VertexArray {
@@ -15,9 +25,11 @@ impl VertexArray {
         unsafe {
             gl::GenVertexArrays(1, &mut va);
         }
+        leak_tracking::track_created(GlObjectKind::VertexArray, va);
 
         let vao = VertexArray {
             opengl_id: va,
+            used_locations: BTreeSet::new(),
         };
 
         vao.bind();
@@ -25,29 +37,66 @@ impl VertexArray {
         return vao;
     }
 
-    pub fn add_buffer(&mut self, vb: &VertexBuffer, layout: &VertexBufferLayout) {
+    /// Adds the attributes of the vertex buffer as described by the layout.
+    /// The vertex buffer must be kept alive as long as this vertex array is used, as it is deleted when dropped.
+    pub fn add_buffer<T: Pod>(&mut self, vb: &VertexBuffer<T>, layout: &VertexBufferLayout) {
         self.bind();
         vb.bind();
 
-        let mut current_attribute_index = 0;
-        let mut offset = 0;
+        for attribute in &layout.attributes {
+            let first_location = match attribute.location {
+                Some(location) => {
+                    let used = (location..location + attribute.columns).find(|location| self.used_locations.contains(location));
+                    assert!(used.is_none(), "[Bowl] Attribute location {} is already used by this vertex array", used.unwrap_or(location));
+                    location
+                }
+                None => self.next_attribute_location(attribute.columns),
+            };
 
-        for (r#type, element_count) in &layout.attributes {
-            let (gl_type, size_per_element) = r#type.to_gl_enum_and_size();
+            let (gl_type, _) = attribute.r#type.to_gl_enum_and_size();
+            let stride = attribute.stride.unwrap_or(layout.stride);
+            let divisor = attribute.divisor.unwrap_or(layout.divisor);
+            let column_size = attribute.r#type.attribute_size(attribute.count);
 
-            unsafe {
-                gl::VertexAttribPointer(current_attribute_index, *element_count as GLsizei, gl_type, 0, layout.stride as GLsizei, offset as *const c_void);
-                gl::EnableVertexAttribArray(current_attribute_index);
-            }
+            for column in 0..attribute.columns {
+                let location = first_location + column;
+                let offset = (attribute.offset + column * column_size) as *const c_void;
+                self.used_locations.insert(location);
 
-            current_attribute_index += 1;
-            offset += element_count * size_per_element;
+                unsafe {
+                    // Integer attributes that are not normalized would be converted to floats by glVertexAttribPointer
+                    if attribute.r#type.is_integer() && !attribute.normalized {
+                        gl::VertexAttribIPointer(location, attribute.count as GLint, gl_type, stride as GLsizei, offset);
+                    } else {
+                        gl::VertexAttribPointer(location, attribute.count as GLint, gl_type, attribute.normalized as GLboolean, stride as GLsizei, offset);
+                    }
+                    gl::VertexAttribDivisor(location, divisor);
+                    gl::EnableVertexAttribArray(location);
+                }
+            }
         }
     }
 
+    /// Adds the attributes of the instance type, which advance once per instance of an instanced draw call
+    pub fn add_instance_buffer<T: VertexLayout + Pod>(&mut self, instances: &InstanceBuffer<T>) {
+        self.add_buffer(instances.vertex_buffer(), &InstanceBuffer::<T>::layout());
+    }
+
+    /// The lowest location that is followed by `count` locations not used by any attribute of this vertex array yet
+    pub fn next_attribute_location(&self, count: u32) -> GLuint {
+        (0..).find(|first| (*first..first + count).all(|location| !self.used_locations.contains(&location))).unwrap()
+    }
+
     pub fn bind(&self) {
         unsafe {
             gl::BindVertexArray(self.opengl_id);
         }
     }
-}
\ No newline at end of file
+}
+
+impl Drop for VertexArray {
+    fn drop(&mut self) {
+        gl_call!(gl::DeleteVertexArrays(1, &self.opengl_id));
+        leak_tracking::track_deleted(GlObjectKind::VertexArray, self.opengl_id);
+    }
+}
@@ -1,8 +1,65 @@
+use glam::{IVec2, IVec3, IVec4, Mat2, Mat3, Mat4, UVec2, UVec3, UVec4, Vec2, Vec3, Vec4};
+
 use crate::data::Type;
 
+pub use bowl_derive::VertexLayout;
+
 pub struct VertexBufferLayout {
     pub(crate) stride: u32,
-    pub(crate) attributes: Vec<(Type, u32)>,
+    pub(crate) attributes: Vec<Attribute>,
+    /// The divisor of all attributes without an explicit one
+    pub(crate) divisor: u32,
+}
+
+pub(crate) struct Attribute {
+    pub(crate) r#type: Type,
+    pub(crate) count: u32,
+    /// Number of consecutive locations the attribute occupies, e.g. 4 for a `mat4` with a count of 4 per column
+    pub(crate) columns: u32,
+    /// Offset in bytes from the start of a vertex
+    pub(crate) offset: u32,
+    /// Whether integer components are converted to floats in the range [0, 1], or [-1, 1] for signed types
+    pub(crate) normalized: bool,
+    /// The explicit attribute location, otherwise the vertex array assigns the next free location
+    pub(crate) location: Option<u32>,
+    /// Overrides the stride of the layout, e.g. for non-interleaved attributes
+    pub(crate) stride: Option<u32>,
+    /// Overrides the divisor of the layout
+    pub(crate) divisor: Option<u32>,
+}
+
+/// Describes a single attribute added with `VertexBufferLayout::add`.
+///
+/// Attributes of interleaved buffers only need an offset within a vertex. For non-interleaved buffers, where all
+/// values of one attribute are stored after each other, the offset is the start of the attribute's values in the
+/// buffer and the stride is the distance between two values, or 0 if they are tightly packed.
+#[derive(Clone, Copy, Debug)]
+pub struct AttributeDesc {
+    r#type: Type,
+    count: u32,
+    columns: u32,
+    offset: Option<u32>,
+    location: Option<u32>,
+    stride: Option<u32>,
+    divisor: Option<u32>,
+    normalized: bool,
+}
+
+/// A vertex type whose memory layout is described by a `VertexBufferLayout`.
+///
+/// Usually derived with `#[derive(VertexLayout)]` for `#[repr(C)]` structs, which adds one attribute per field.
+/// Fields marked with `#[vertex(normalized)]` are added as normalized attributes.
+pub trait VertexLayout {
+    fn layout() -> VertexBufferLayout;
+}
+
+/// A type that can be used as a field of a struct deriving `VertexLayout`
+pub trait VertexAttribute {
+    const TYPE: Type;
+    /// The number of components per column
+    const COUNT: u32;
+    /// Matrices occupy one attribute location per column
+    const COLUMNS: u32 = 1;
 }
 
 
@@ -11,11 +68,171 @@ impl VertexBufferLayout {
         VertexBufferLayout {
             stride: 0,
             attributes: Vec::new(),
+            divisor: 0,
         }
     }
 
+    /// Creates a layout with a fixed stride, e.g. the size of a vertex struct including padding
+    pub fn with_stride(stride: u32) -> Self {
+        VertexBufferLayout {
+            stride,
+            attributes: Vec::new(),
+            divisor: 0,
+        }
+    }
+
+    /// Adds an attribute directly after the previous one.
+    /// Integer attributes added this way are read as integers in the shader, e.g. as `ivec4` or `uint`.
     pub fn add_attribute(&mut self, r#type: Type, count: u32) {
-        self.attributes.push((r#type, count));
-        self.stride += count * r#type.to_gl_enum_and_size().1;
+        self.add_attribute_with_offset(r#type, count, self.next_offset(), false);
+    }
+
+    /// Adds an attribute directly after the previous one, whose integer components are read as normalized floats
+    /// in the shader, e.g. `UnsignedByte` colors in the range [0, 1]
+    pub fn add_normalized_attribute(&mut self, r#type: Type, count: u32) {
+        self.add_attribute_with_offset(r#type, count, self.next_offset(), true);
     }
-}
\ No newline at end of file
+
+    /// Adds an attribute at the given offset in bytes from the start of a vertex.
+    /// The stride grows to include the attribute, if necessary.
+    pub fn add_attribute_with_offset(&mut self, r#type: Type, count: u32, offset: u32, normalized: bool) {
+        self.add(AttributeDesc::new(r#type, count).offset(offset).normalized(normalized));
+    }
+
+    /// Adds an attribute described by `desc`. Without an explicit offset, the attribute is placed directly after
+    /// the previous one. Unless the attribute has its own stride, the stride grows to include it, if necessary.
+    pub fn add(&mut self, desc: AttributeDesc) {
+        let AttributeDesc { r#type, count, columns, offset, location, stride, divisor, normalized } = desc;
+        assert!(!r#type.is_packed() || count == 4, "[Bowl] Attributes of type {:?} must have 4 components, but got {}", r#type, count);
+
+        let offset = offset.unwrap_or_else(|| self.next_offset());
+        self.attributes.push(Attribute { r#type, count, columns, offset, normalized, location, stride, divisor });
+
+        if stride.is_none() {
+            self.stride = self.stride.max(offset + columns * r#type.attribute_size(count));
+        }
+    }
+
+    /// Sets the divisor of all attributes without an explicit one. With a divisor of 0 the attributes advance
+    /// per vertex, otherwise they advance once per `divisor` instances of an instanced draw call.
+    pub fn set_divisor(&mut self, divisor: u32) {
+        self.divisor = divisor;
+    }
+
+    pub fn stride(&self) -> u32 {
+        self.stride
+    }
+
+    fn next_offset(&self) -> u32 {
+        self.attributes.last()
+            .map(|attribute| attribute.offset + attribute.columns * attribute.r#type.attribute_size(attribute.count))
+            .unwrap_or(0)
+    }
+}
+
+impl AttributeDesc {
+    pub fn new(r#type: Type, count: u32) -> Self {
+        Self {
+            r#type,
+            count,
+            columns: 1,
+            offset: None,
+            location: None,
+            stride: None,
+            divisor: None,
+            normalized: false,
+        }
+    }
+
+    /// The number of columns of a matrix attribute, each with `count` components and its own location
+    pub fn columns(mut self, columns: u32) -> Self {
+        self.columns = columns;
+        return self;
+    }
+    /// Offset in bytes from the start of a vertex, or from the start of the buffer for non-interleaved attributes
+    pub fn offset(mut self, offset: u32) -> Self {
+        self.offset = Some(offset);
+        return self;
+    }
+    /// The location of the attribute, matching `layout(location = N)` in GLSL
+    pub fn location(mut self, location: u32) -> Self {
+        self.location = Some(location);
+        return self;
+    }
+    pub fn stride(mut self, stride: u32) -> Self {
+        self.stride = Some(stride);
+        return self;
+    }
+    pub fn divisor(mut self, divisor: u32) -> Self {
+        self.divisor = Some(divisor);
+        return self;
+    }
+    pub fn normalized(mut self, normalized: bool) -> Self {
+        self.normalized = normalized;
+        return self;
+    }
+}
+
+macro_rules! impl_vertex_attribute {
+    ($($attribute:ty => $type:ident, $count:expr;)*) => {
+        $(
+            impl VertexAttribute for $attribute {
+                const TYPE: Type = Type::$type;
+                const COUNT: u32 = $count;
+            }
+        )*
+    };
+}
+
+impl_vertex_attribute! {
+    f32 => Float, 1;
+    i8 => Byte, 1;
+    u8 => UnsignedByte, 1;
+    i16 => Short, 1;
+    u16 => UnsignedShort, 1;
+    i32 => Int, 1;
+    u32 => UnsignedInt, 1;
+    Vec2 => Float, 2;
+    Vec3 => Float, 3;
+    Vec4 => Float, 4;
+    IVec2 => Int, 2;
+    IVec3 => Int, 3;
+    IVec4 => Int, 4;
+    UVec2 => UnsignedInt, 2;
+    UVec3 => UnsignedInt, 3;
+    UVec4 => UnsignedInt, 4;
+}
+
+macro_rules! impl_matrix_vertex_attribute {
+    ($($attribute:ty => $columns:expr;)*) => {
+        $(
+            impl VertexAttribute for $attribute {
+                const TYPE: Type = Type::Float;
+                const COUNT: u32 = $columns;
+                const COLUMNS: u32 = $columns;
+            }
+        )*
+    };
+}
+
+impl_matrix_vertex_attribute! {
+    Mat2 => 2;
+    Mat3 => 3;
+    Mat4 => 4;
+}
+
+macro_rules! impl_array_vertex_attribute {
+    ($($length:expr),*) => {
+        $(
+            /// Arrays of scalars are vectors, arrays of vectors and matrices occupy one location per element or column
+            impl<T: VertexAttribute> VertexAttribute for [T; $length] {
+                const TYPE: Type = T::TYPE;
+                const COUNT: u32 = if T::COUNT == 1 && T::COLUMNS == 1 { $length } else { T::COUNT };
+                const COLUMNS: u32 = if T::COUNT == 1 && T::COLUMNS == 1 { 1 } else { T::COLUMNS * $length };
+            }
+        )*
+    };
+}
+
+// Vertex attributes have at most 4 components
+impl_array_vertex_attribute!(1, 2, 3, 4);
@@ -0,0 +1,386 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::os::raw::c_void;
+
+use gl::types::{GLenum, GLint, GLsizei, GLuint};
+use glam::Vec4;
+
+use crate::data::image::RgbaImage;
+use crate::gl_call;
+use crate::leak_tracking::{self, GlObjectKind};
+use crate::texture::{Texture2D, TextureFormat};
+
+/// An OpenGL renderbuffer, a render target that can not be sampled in shaders.
+///
+/// Renderbuffers are mostly used as depth and stencil attachments, when only the color output of a framebuffer is sampled.
+pub struct Renderbuffer {
+    opengl_id: GLuint,
+    format: TextureFormat,
+    width: u32,
+    height: u32,
+}
+
+/// The storage of a single framebuffer attachment
+pub enum Attachment {
+    Texture(Texture2D),
+    Renderbuffer(Renderbuffer),
+}
+
+#[derive(Debug)]
+pub enum FramebufferError {
+    /// A depth or stencil format was used for a color attachment, or a color format for the depth attachment
+    InvalidAttachmentFormat {
+        format: TextureFormat,
+    },
+    /// `glCheckFramebufferStatus` did not return `GL_FRAMEBUFFER_COMPLETE`
+    Incomplete {
+        status: GLenum,
+    },
+}
+
+/// An offscreen render target with one or more color attachments and an optional depth or depth/stencil attachment
+pub struct Framebuffer {
+    opengl_id: GLuint,
+    width: u32,
+    height: u32,
+    color_attachments: Vec<Attachment>,
+    depth_attachment: Option<Attachment>,
+}
+
+pub struct FramebufferBuilder {
+    width: u32,
+    height: u32,
+    color_attachments: Vec<(TextureFormat, bool)>,
+    depth_attachment: Option<(TextureFormat, bool)>,
+}
+
+impl FramebufferBuilder {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            color_attachments: Vec::new(),
+            depth_attachment: None,
+        }
+    }
+
+    /// Adds a color attachment that can be sampled after rendering, see `Framebuffer::color_texture`
+    pub fn color_texture(mut self, format: TextureFormat) -> Self {
+        self.color_attachments.push((format, true));
+        return self;
+    }
+    pub fn color_renderbuffer(mut self, format: TextureFormat) -> Self {
+        self.color_attachments.push((format, false));
+        return self;
+    }
+    /// Sets the depth attachment to a texture that can be sampled after rendering, see `Framebuffer::depth_texture`.
+    /// The format may also contain a stencil component.
+    pub fn depth_texture(mut self, format: TextureFormat) -> Self {
+        self.depth_attachment = Some((format, true));
+        return self;
+    }
+    /// Sets the depth attachment to a renderbuffer, the format may also contain a stencil component
+    pub fn depth_renderbuffer(mut self, format: TextureFormat) -> Self {
+        self.depth_attachment = Some((format, false));
+        return self;
+    }
+
+    pub fn create(self) -> Result<Framebuffer, FramebufferError> {
+        for (format, _) in &self.color_attachments {
+            if format.is_depth() {
+                return Err(FramebufferError::InvalidAttachmentFormat { format: *format });
+            }
+        }
+        if let Some((format, _)) = self.depth_attachment {
+            if !format.is_depth() {
+                return Err(FramebufferError::InvalidAttachmentFormat { format });
+            }
+        }
+
+        let create_attachment = |(format, is_texture): (TextureFormat, bool)| {
+            if is_texture {
+                Attachment::Texture(Texture2D::empty(self.width, self.height, format))
+            } else {
+                Attachment::Renderbuffer(Renderbuffer::new(self.width, self.height, format))
+            }
+        };
+
+        let mut opengl_id: GLuint = 0;
+        gl_call!(gl::GenFramebuffers(1, &mut opengl_id));
+        leak_tracking::track_created(GlObjectKind::Framebuffer, opengl_id);
+
+        let framebuffer = Framebuffer {
+            opengl_id,
+            width: self.width,
+            height: self.height,
+            color_attachments: self.color_attachments.iter().copied().map(create_attachment).collect(),
+            depth_attachment: self.depth_attachment.map(create_attachment),
+        };
+        framebuffer.attach()?;
+
+        Ok(framebuffer)
+    }
+}
+
+impl Framebuffer {
+    /// Binds this framebuffer as target of all following draw calls.
+    /// The viewport is not changed, see `WindowHandle::render_to` for rendering with a matching viewport.
+    pub fn bind(&self) {
+        gl_call!(gl::BindFramebuffer(gl::FRAMEBUFFER, self.opengl_id));
+    }
+
+    /// Binds the default framebuffer of the window
+    pub fn unbind() {
+        gl_call!(gl::BindFramebuffer(gl::FRAMEBUFFER, 0));
+    }
+
+    /// Clears all attachments, the color attachments are cleared to the given color, converted to integers for
+    /// integer formats. The bound framebuffer and the clear values of the context are not changed.
+    pub fn clear(&self, color: Vec4) {
+        let mut previous_framebuffer: GLint = 0;
+        gl_call!(gl::GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut previous_framebuffer));
+
+        gl_call!(gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, self.opengl_id));
+
+        for (index, attachment) in self.color_attachments.iter().enumerate() {
+            let draw_buffer = index as GLint;
+            match attachment.format().to_gl_enums().2 {
+                _ if !attachment.format().is_integer() => gl_call!(gl::ClearBufferfv(gl::COLOR, draw_buffer, color.as_ref().as_ptr())),
+                gl::BYTE | gl::SHORT | gl::INT => gl_call!(gl::ClearBufferiv(gl::COLOR, draw_buffer, color.as_ivec4().as_ref().as_ptr())),
+                _ => gl_call!(gl::ClearBufferuiv(gl::COLOR, draw_buffer, color.as_uvec4().as_ref().as_ptr())),
+            }
+        }
+
+        if let Some(attachment) = &self.depth_attachment {
+            if attachment.format().has_stencil() {
+                gl_call!(gl::ClearBufferfi(gl::DEPTH_STENCIL, 0, 1.0, 0));
+            } else {
+                gl_call!(gl::ClearBufferfv(gl::DEPTH, 0, &1.0));
+            }
+        }
+
+        gl_call!(gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, previous_framebuffer as GLuint));
+    }
+
+    /// Resizes all attachments. Their contents are undefined afterwards.
+    pub fn resize(&mut self, width: u32, height: u32) -> Result<(), FramebufferError> {
+        if width == self.width && height == self.height {
+            return Ok(());
+        }
+
+        for attachment in self.color_attachments.iter_mut().chain(self.depth_attachment.iter_mut()) {
+            match attachment {
+                Attachment::Texture(texture) => texture.resize(width, height),
+                Attachment::Renderbuffer(renderbuffer) => renderbuffer.resize(width, height),
+            }
+        }
+        self.width = width;
+        self.height = height;
+
+        self.attach()
+    }
+
+    /// The texture of the color attachment with the given index, if it is a texture
+    pub fn color_texture(&self, index: usize) -> Option<&Texture2D> {
+        match self.color_attachments.get(index) {
+            Some(Attachment::Texture(texture)) => Some(texture),
+            _ => None,
+        }
+    }
+
+    /// The texture of the depth attachment, if it is a texture
+    pub fn depth_texture(&self) -> Option<&Texture2D> {
+        match &self.depth_attachment {
+            Some(Attachment::Texture(texture)) => Some(texture),
+            _ => None,
+        }
+    }
+
+    /// Reads the pixels of the color attachment with the given index, converted to 8-bit RGBA.
+    /// Attachments with integer formats can not be read this way.
+    pub fn read_pixels(&self, index: usize) -> RgbaImage {
+        assert!(index < self.color_attachments.len(),
+                "[Bowl] Can not read pixels of color attachment {} of a framebuffer with {} color attachments", index, self.color_attachments.len());
+
+        read_pixels(self.opengl_id, gl::COLOR_ATTACHMENT0 + index as GLenum, self.width, self.height)
+    }
+
+    pub fn color_attachments(&self) -> &[Attachment] {
+        &self.color_attachments
+    }
+
+    pub fn depth_attachment(&self) -> Option<&Attachment> {
+        self.depth_attachment.as_ref()
+    }
+
+    pub fn opengl_id(&self) -> GLuint {
+        self.opengl_id
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Attaches all attachments and checks the framebuffer for completeness
+    fn attach(&self) -> Result<(), FramebufferError> {
+        let mut previous_framebuffer: GLint = 0;
+        gl_call!(gl::GetIntegerv(gl::FRAMEBUFFER_BINDING, &mut previous_framebuffer));
+
+        self.bind();
+
+        let mut draw_buffers = Vec::new();
+        for (index, attachment) in self.color_attachments.iter().enumerate() {
+            let attachment_point = gl::COLOR_ATTACHMENT0 + index as GLenum;
+            attachment.attach(attachment_point);
+            draw_buffers.push(attachment_point);
+        }
+
+        if let Some(attachment) = &self.depth_attachment {
+            let attachment_point = if attachment.format().has_stencil() { gl::DEPTH_STENCIL_ATTACHMENT } else { gl::DEPTH_ATTACHMENT };
+            attachment.attach(attachment_point);
+        }
+
+        if draw_buffers.is_empty() {
+            gl_call!(gl::DrawBuffer(gl::NONE));
+            gl_call!(gl::ReadBuffer(gl::NONE));
+        } else {
+            gl_call!(gl::DrawBuffers(draw_buffers.len() as GLsizei, draw_buffers.as_ptr()));
+        }
+
+        let status = gl_call!(gl::CheckFramebufferStatus(gl::FRAMEBUFFER));
+        gl_call!(gl::BindFramebuffer(gl::FRAMEBUFFER, previous_framebuffer as GLuint));
+
+        if status != gl::FRAMEBUFFER_COMPLETE {
+            return Err(FramebufferError::Incomplete { status });
+        }
+        Ok(())
+    }
+}
+
+/// Reads 8-bit RGBA pixels from the given buffer of a framebuffer, e.g. `GL_BACK` of the default framebuffer 0
+pub(crate) fn read_pixels(framebuffer: GLuint, read_buffer: GLenum, width: u32, height: u32) -> RgbaImage {
+    let mut previous_framebuffer: GLint = 0;
+    gl_call!(gl::GetIntegerv(gl::READ_FRAMEBUFFER_BINDING, &mut previous_framebuffer));
+
+    gl_call!(gl::BindFramebuffer(gl::READ_FRAMEBUFFER, framebuffer));
+    gl_call!(gl::ReadBuffer(read_buffer));
+
+    let mut data: Vec<u8> = vec![0; width as usize * height as usize * 4];
+    gl_call!(gl::PixelStorei(gl::PACK_ALIGNMENT, 1));
+    gl_call!(gl::ReadPixels(0, 0, width as GLsizei, height as GLsizei, gl::RGBA, gl::UNSIGNED_BYTE, data.as_mut_ptr() as *mut c_void));
+    gl_call!(gl::PixelStorei(gl::PACK_ALIGNMENT, 4));
+
+    gl_call!(gl::BindFramebuffer(gl::READ_FRAMEBUFFER, previous_framebuffer as GLuint));
+
+    RgbaImage::from_bottom_to_top_rows(width, height, data)
+}
+
+impl Drop for Framebuffer {
+    fn drop(&mut self) {
+        gl_call!(gl::DeleteFramebuffers(1, &self.opengl_id));
+        leak_tracking::track_deleted(GlObjectKind::Framebuffer, self.opengl_id);
+    }
+}
+
+impl Renderbuffer {
+    pub fn new(width: u32, height: u32, format: TextureFormat) -> Self {
+        let mut opengl_id: GLuint = 0;
+        gl_call!(gl::GenRenderbuffers(1, &mut opengl_id));
+        leak_tracking::track_created(GlObjectKind::Renderbuffer, opengl_id);
+
+        let mut renderbuffer = Self {
+            opengl_id,
+            format,
+            width,
+            height,
+        };
+        renderbuffer.resize(width, height);
+
+        renderbuffer
+    }
+
+    /// Reallocates the storage of this renderbuffer with the given size. The contents are undefined afterwards.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        let (internal_format, _, _) = self.format.to_gl_enums();
+
+        gl_call!(gl::BindRenderbuffer(gl::RENDERBUFFER, self.opengl_id));
+        gl_call!(gl::RenderbufferStorage(gl::RENDERBUFFER, internal_format, width as GLsizei, height as GLsizei));
+
+        self.width = width;
+        self.height = height;
+    }
+
+    pub fn opengl_id(&self) -> GLuint {
+        self.opengl_id
+    }
+
+    pub fn format(&self) -> TextureFormat {
+        self.format
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+}
+
+impl Drop for Renderbuffer {
+    fn drop(&mut self) {
+        gl_call!(gl::DeleteRenderbuffers(1, &self.opengl_id));
+        leak_tracking::track_deleted(GlObjectKind::Renderbuffer, self.opengl_id);
+    }
+}
+
+impl Attachment {
+    pub fn format(&self) -> TextureFormat {
+        match self {
+            Attachment::Texture(texture) => texture.format(),
+            Attachment::Renderbuffer(renderbuffer) => renderbuffer.format(),
+        }
+    }
+
+    /// Attaches this attachment to the given attachment point of the bound framebuffer
+    fn attach(&self, attachment_point: GLenum) {
+        match self {
+            Attachment::Texture(texture) => {
+                gl_call!(gl::FramebufferTexture2D(gl::FRAMEBUFFER, attachment_point, gl::TEXTURE_2D, texture.opengl_id(), 0));
+            }
+            Attachment::Renderbuffer(renderbuffer) => {
+                gl_call!(gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, attachment_point, gl::RENDERBUFFER, renderbuffer.opengl_id()));
+            }
+        }
+    }
+}
+
+impl Display for FramebufferError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FramebufferError::InvalidAttachmentFormat { format } => {
+                write!(f, "[Bowl] Texture format {:?} can not be used for this framebuffer attachment", format)
+            }
+            FramebufferError::Incomplete { status } => {
+                let reason = match *status {
+                    gl::FRAMEBUFFER_UNDEFINED => "undefined",
+                    gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT => "incomplete attachment",
+                    gl::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT => "missing attachment",
+                    gl::FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER => "incomplete draw buffer",
+                    gl::FRAMEBUFFER_INCOMPLETE_READ_BUFFER => "incomplete read buffer",
+                    gl::FRAMEBUFFER_UNSUPPORTED => "unsupported combination of attachment formats",
+                    gl::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE => "incomplete multisample",
+                    gl::FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS => "incomplete layer targets",
+                    _ => "unknown status",
+                };
+                write!(f, "[Bowl] Framebuffer is not complete: {} (status {:#x})", reason, status)
+            }
+        }
+    }
+}
+
+impl Error for FramebufferError {}
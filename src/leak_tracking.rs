@@ -0,0 +1,92 @@
+//! Tracking of live OpenGL objects, enabled with the `leak-tracking` feature.
+//!
+//! Every OpenGL object created by bowl is registered together with a backtrace of its creation, until it is deleted.
+//! When a `Window` is dropped, all objects that are still alive are reported as leaks. `check` reports them at any
+//! other point. Without the feature, nothing is tracked, `live_object_counts` is always empty and nothing is reported.
+
+#[cfg(feature = "leak-tracking")]
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+#[cfg(feature = "leak-tracking")]
+use std::collections::HashMap;
+
+#[cfg(feature = "leak-tracking")]
+use backtrace::Backtrace;
+use gl::types::GLuint;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum GlObjectKind {
+    Buffer,
+    VertexArray,
+    Texture,
+    Sampler,
+    Renderbuffer,
+    Framebuffer,
+    Shader,
+    Program,
+}
+
+#[cfg(feature = "leak-tracking")]
+thread_local! {
+    // OpenGL contexts are bound to a thread, so are their objects
+    static LIVE_OBJECTS: RefCell<HashMap<(GlObjectKind, GLuint), Backtrace>> = RefCell::new(HashMap::new());
+}
+
+/// The number of live OpenGL objects per kind
+#[cfg(feature = "leak-tracking")]
+pub fn live_object_counts() -> BTreeMap<GlObjectKind, usize> {
+    LIVE_OBJECTS.with(|objects| {
+        let mut counts = BTreeMap::new();
+        for (kind, _) in objects.borrow().keys() {
+            *counts.entry(*kind).or_insert(0) += 1;
+        }
+        counts
+    })
+}
+
+#[cfg(not(feature = "leak-tracking"))]
+pub fn live_object_counts() -> BTreeMap<GlObjectKind, usize> {
+    BTreeMap::new()
+}
+
+#[cfg(feature = "leak-tracking")]
+pub(crate) fn track_created(kind: GlObjectKind, opengl_id: GLuint) {
+    // Resolving the symbols is expensive, so it is deferred until a leak is reported
+    LIVE_OBJECTS.with(|objects| objects.borrow_mut().insert((kind, opengl_id), Backtrace::new_unresolved()));
+}
+
+#[cfg(not(feature = "leak-tracking"))]
+pub(crate) fn track_created(_kind: GlObjectKind, _opengl_id: GLuint) {}
+
+#[cfg(feature = "leak-tracking")]
+pub(crate) fn track_deleted(kind: GlObjectKind, opengl_id: GLuint) {
+    LIVE_OBJECTS.with(|objects| objects.borrow_mut().remove(&(kind, opengl_id)));
+}
+
+#[cfg(not(feature = "leak-tracking"))]
+pub(crate) fn track_deleted(_kind: GlObjectKind, _opengl_id: GLuint) {}
+
+/// Logs all live objects together with the backtraces of their creation and returns their number.
+///
+/// This is called when a `Window` is dropped, after its own objects are deleted.
+#[cfg(feature = "leak-tracking")]
+pub fn check() -> usize {
+    LIVE_OBJECTS.with(|objects| {
+        let mut objects = objects.borrow_mut();
+        if objects.is_empty() {
+            return 0;
+        }
+
+        eprintln!("[Bowl] {} OpenGL objects are still alive", objects.len());
+        for ((kind, opengl_id), backtrace) in objects.iter_mut() {
+            backtrace.resolve();
+            eprintln!("[Bowl] Leaked {:?} {}, created at:\n{:?}", kind, opengl_id, backtrace);
+        }
+        objects.len()
+    })
+}
+
+#[cfg(not(feature = "leak-tracking"))]
+pub fn check() -> usize {
+    0
+}
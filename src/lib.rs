@@ -1,9 +1,17 @@
 extern crate core;
+// Allows the derive macros, which refer to ::bowl, to be used inside of this crate
+extern crate self as bowl;
 
+pub mod camera;
 pub mod shader;
+pub mod texture;
 pub mod window;
 pub mod data;
+pub mod framebuffer;
 pub mod renderable;
 pub mod vertex;
 pub mod gl_error_handling;
+pub mod leak_tracking;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub(crate) mod util;
\ No newline at end of file
@@ -0,0 +1,100 @@
+use std::ffi::c_void;
+
+use gl::types::{GLsizei, GLuint};
+
+use crate::data::buffer::Pod;
+use crate::data::buffer_mode::DrawMode;
+use crate::data::indices_array::{Index, IndicesBuffer};
+use crate::data::instance_buffer::InstanceBuffer;
+use crate::data::vertex_array::VertexArray;
+use crate::data::vertex_buffer::VertexBuffer;
+use crate::data::vertex_buffer_layout::VertexLayout;
+use crate::renderable::Renderable;
+use crate::vertex::Vertex;
+
+/// A mesh that is drawn once per instance in a single draw call.
+///
+/// The attributes of the instance type `T` are assigned the locations following the ones of `Vertex`,
+/// i.e. starting at location 3.
+pub struct InstancedMesh<T: VertexLayout + Pod> {
+    vao: VertexArray,
+    // Kept alive for the vertex array, which references it
+    _vbo: VertexBuffer<Vertex>,
+    ib: Option<IndicesBuffer>,
+    vertex_count: usize,
+    instances: InstanceBuffer<T>,
+}
+
+impl<T: VertexLayout + Pod> InstancedMesh<T> {
+    pub fn new(vertices: &[Vertex], instances: &[T]) -> Self {
+        Self::with_instance_buffer(vertices, InstanceBuffer::new(instances, DrawMode::DYNAMIC))
+    }
+
+    /// Creates a mesh whose triangles are described by indices into `vertices`
+    pub fn with_indices<I: Index>(vertices: &[Vertex], indices: &[I], instances: &[T]) -> Self {
+        let mut mesh = Self::new(vertices, instances);
+        mesh.set_indices(indices);
+        mesh
+    }
+
+    /// Creates a mesh drawing the instances of the given buffer, e.g. one created with
+    /// `InstanceBuffer::with_capacity(n, DrawMode::STREAM_PERSISTENT)` for instances that are replaced every frame
+    pub fn with_instance_buffer(vertices: &[Vertex], instances: InstanceBuffer<T>) -> Self {
+        let mut vao = VertexArray::new();
+        let vbo = VertexBuffer::new(vertices, DrawMode::STATIC);
+        vao.add_buffer(&vbo, &Vertex::layout());
+        vao.add_instance_buffer(&instances);
+
+        Self {
+            vao,
+            _vbo: vbo,
+            ib: None,
+            vertex_count: vertices.len(),
+            instances,
+        }
+    }
+
+    /// Replaces the indices of this mesh, or adds them if it had none
+    pub fn set_indices<I: Index>(&mut self, indices: &[I]) {
+        match &mut self.ib {
+            Some(ib) => ib.set_data(indices),
+            None => self.ib = Some(IndicesBuffer::new(indices, DrawMode::STATIC)),
+        }
+    }
+
+    /// Replaces all instances, e.g. once per frame
+    pub fn set_instances(&mut self, instances: &[T]) {
+        self.instances.set_data(instances);
+    }
+
+    pub fn instance_count(&self) -> usize {
+        self.instances.len()
+    }
+}
+
+impl<T: VertexLayout + Pod> Renderable for InstancedMesh<T> {
+    fn render(&self) {
+        self.vao.bind();
+
+        let instance_count = self.instances.len() as GLsizei;
+        // Non-zero for persistent ring buffers, whose current instances start in a later segment
+        let base_instance = self.instances.element_offset() as GLuint;
+
+        unsafe {
+            match &self.ib {
+                Some(ib) => {
+                    ib.bind();
+                    let count = ib.len() as GLsizei;
+                    let offset = ib.byte_offset() as *const c_void;
+                    if base_instance == 0 {
+                        gl::DrawElementsInstanced(gl::TRIANGLES, count, ib.index_type().to_gl_enum(), offset, instance_count);
+                    } else {
+                        gl::DrawElementsInstancedBaseInstance(gl::TRIANGLES, count, ib.index_type().to_gl_enum(), offset, instance_count, base_instance);
+                    }
+                }
+                None if base_instance == 0 => gl::DrawArraysInstanced(gl::TRIANGLES, 0, self.vertex_count as GLsizei, instance_count),
+                None => gl::DrawArraysInstancedBaseInstance(gl::TRIANGLES, 0, self.vertex_count as GLsizei, instance_count, base_instance),
+            }
+        }
+    }
+}
@@ -1,50 +1,184 @@
-use gl::types::GLsizei;
-use crate::data::buffer_mode::DrawMode;
+use std::ffi::c_void;
+use std::ops::Range;
 
-use crate::data::Type;
+use gl::types::{GLint, GLsizei};
+use crate::data::buffer_mode::{BufferMode, DrawMode};
+
+use crate::data::indices_array::{Index, IndicesBuffer};
 use crate::data::vertex_array::VertexArray;
 use crate::data::vertex_buffer::VertexBuffer;
-use crate::data::vertex_buffer_layout::VertexBufferLayout;
+use crate::data::vertex_buffer_layout::VertexLayout;
+use crate::gl_call;
 use crate::renderable::Renderable;
+use crate::renderable::topology::PrimitiveTopology;
 use crate::vertex::Vertex;
 
+/// A list of vertices, optionally with indices into them, that are assembled into primitives of the mesh's topology.
 pub struct Mesh {
-    vertices: Vec<Vertex>,
     vao: VertexArray,
-    vbo: VertexBuffer,
+    vbo: VertexBuffer<Vertex>,
+    ib: Option<IndicesBuffer>,
+    topology: PrimitiveTopology,
+    primitive_restart: bool,
+}
+
+/// A range of the vertices, or of the indices if the mesh has any, that is rendered instead of the whole mesh
+pub struct MeshRange<'a> {
+    mesh: &'a Mesh,
+    range: Range<usize>,
 }
 
 impl Mesh {
     pub fn new(vertices: Vec<Vertex>) -> Mesh {
-        let mut va = VertexArray::new();
-        let vb = VertexBuffer::new(&vertices, DrawMode::DYNAMIC);
-        let mut layout = VertexBufferLayout::new();
+        Self::with_draw_mode(&vertices, DrawMode::DYNAMIC)
+    }
+
+    /// Creates a mesh whose vertex buffer is updated as selected by `draw_mode`, e.g. `DrawMode::STREAM` for
+    /// vertices that are replaced every frame
+    pub fn with_draw_mode(vertices: &[Vertex], draw_mode: impl BufferMode) -> Mesh {
+        Self::from_vertex_buffer(VertexBuffer::new(vertices, draw_mode))
+    }
 
-        layout.add_attribute(Type::Float, 3);
-        layout.add_attribute(Type::Float, 3);
-        layout.add_attribute(Type::Float, 2);
-        va.add_buffer(&vb, &layout);
+    /// Creates an empty mesh with storage for `capacity` vertices, e.g. for a `DrawMode::STREAM_PERSISTENT` mesh,
+    /// whose vertices can not grow beyond the capacity
+    pub fn with_capacity(capacity: usize, draw_mode: impl BufferMode) -> Mesh {
+        Self::from_vertex_buffer(VertexBuffer::with_capacity(capacity, draw_mode))
+    }
+
+    fn from_vertex_buffer(vb: VertexBuffer<Vertex>) -> Mesh {
+        let mut va = VertexArray::new();
+        va.add_buffer(&vb, &Vertex::layout());
 
         Mesh {
-            vertices,
             vao: va,
             vbo: vb,
+            ib: None,
+            topology: PrimitiveTopology::TRIANGLES,
+            primitive_restart: false,
         }
     }
 
+    /// Creates a mesh whose primitives are assembled from the vertices referenced by `indices`
+    pub fn with_indices<I: Index>(vertices: Vec<Vertex>, indices: &[I]) -> Mesh {
+        let mut mesh = Self::new(vertices);
+        mesh.set_indices(indices);
+        mesh
+    }
+
+    /// Appends the vertices. Only the new vertices are uploaded, the storage grows exponentially if necessary.
     pub fn add_vertices(&mut self, triangle_vertices: Vec<Vertex>) {
-        for vertex in triangle_vertices {
-            self.vertices.push(vertex);
+        self.vbo.extend_from_slice(&triangle_vertices);
+    }
+
+    /// Replaces all vertices, e.g. once per frame for streamed meshes
+    pub fn set_vertices(&mut self, vertices: &[Vertex]) {
+        self.vbo.set_data(vertices);
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.vbo.len()
+    }
+
+    /// Replaces the indices of this mesh, or adds them if it had none
+    pub fn set_indices<I: Index>(&mut self, indices: &[I]) {
+        match &mut self.ib {
+            Some(ib) => ib.set_data(indices),
+            // The index buffer is bound to the vertex array when drawing
+            None => self.ib = Some(IndicesBuffer::new(indices, DrawMode::DYNAMIC)),
         }
-        self.vbo.set_data(&self.vertices);
     }
-}
 
-impl Renderable for Mesh {
-    fn render(&self) {
+    /// Replaces the indices of this mesh with the given buffer, e.g. one created with `DrawMode::STREAM_PERSISTENT`
+    /// for indices that are replaced every frame. `set_indices` updates this buffer afterwards.
+    pub fn set_index_buffer(&mut self, indices: IndicesBuffer) {
+        self.ib = Some(indices);
+    }
+
+    /// Removes the indices, so the vertices are assembled into primitives in order
+    pub fn remove_indices(&mut self) {
+        if self.ib.is_none() {
+            return;
+        }
+
+        // The vertex array still references the index buffer, which must not outlive the binding
+        self.vao.bind();
+        gl_call!(gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, 0));
+        self.ib = None;
+    }
+
+    pub fn set_topology(&mut self, topology: PrimitiveTopology) {
+        self.topology = topology;
+    }
+
+    pub fn topology(&self) -> PrimitiveTopology {
+        self.topology
+    }
+
+    /// Enables primitive restart for meshes with indices: the largest value of the index type, e.g. `u16::MAX`,
+    /// ends the current strip, fan or loop and starts a new one
+    pub fn set_primitive_restart(&mut self, primitive_restart: bool) {
+        self.primitive_restart = primitive_restart;
+    }
+
+    /// The number of vertices, or of indices if the mesh has any
+    pub fn element_count(&self) -> usize {
+        match &self.ib {
+            Some(ib) => ib.len(),
+            None => self.vbo.len(),
+        }
+    }
+
+    /// Only renders the given range of vertices, or of indices if the mesh has any
+    pub fn range(&self, range: Range<usize>) -> MeshRange<'_> {
+        assert!(range.start <= range.end && range.end <= self.element_count(),
+                "[Bowl] Range {:?} is out of bounds of a mesh with {} elements", range, self.element_count());
+        MeshRange { mesh: self, range }
+    }
+
+    fn draw(&self, range: Range<usize>) {
         self.vao.bind();
+        let mode = self.topology.to_gl_enum();
+        let count = (range.end - range.start) as GLsizei;
+        // Non-zero for persistent ring buffers, whose current vertices start in a later segment
+        let base_vertex = self.vbo.element_offset();
+
         unsafe {
-            gl::DrawArrays(gl::TRIANGLES, 0, self.vertices.len() as GLsizei);
+            if let PrimitiveTopology::PATCHES(vertices) = self.topology {
+                gl::PatchParameteri(gl::PATCH_VERTICES, vertices as GLint);
+            }
+
+            match &self.ib {
+                Some(ib) => {
+                    ib.bind();
+                    let index_type = ib.index_type();
+
+                    if self.primitive_restart {
+                        gl::Enable(gl::PRIMITIVE_RESTART);
+                        gl::PrimitiveRestartIndex(index_type.max_value());
+                    }
+
+                    // Persistent ring buffers hold their current indices in a later segment
+                    let offset = (ib.byte_offset() + range.start * index_type.size()) as *const c_void;
+                    gl::DrawElementsBaseVertex(mode, count, index_type.to_gl_enum(), offset, base_vertex as GLint);
+
+                    if self.primitive_restart {
+                        gl::Disable(gl::PRIMITIVE_RESTART);
+                    }
+                }
+                None => gl::DrawArrays(mode, (base_vertex + range.start) as GLint, count),
+            }
         }
     }
-}
\ No newline at end of file
+}
+
+impl Renderable for Mesh {
+    fn render(&self) {
+        self.draw(0..self.element_count());
+    }
+}
+
+impl Renderable for MeshRange<'_> {
+    fn render(&self) {
+        self.mesh.draw(self.range.clone());
+    }
+}
@@ -1,8 +1,14 @@
 mod mesh;
 mod rectangle;
+mod instanced_mesh;
+mod topology;
 
 pub type Mesh = mesh::Mesh;
 pub type Rect = rectangle::Rect;
+pub type InstancedMesh<T> = instanced_mesh::InstancedMesh<T>;
+pub type MeshRange<'a> = mesh::MeshRange<'a>;
+
+pub use topology::PrimitiveTopology;
 
 pub trait Renderable {
     fn render(&self);
@@ -1,12 +1,12 @@
 use std::ptr::null;
 use crate::data::buffer_mode::DrawMode;
 
-use crate::data::Type;
 use crate::data::indices_array::IndicesBuffer;
 use crate::data::vertex_array::VertexArray;
 use crate::data::vertex_buffer::VertexBuffer;
-use crate::data::vertex_buffer_layout::VertexBufferLayout;
+use crate::data::vertex_buffer_layout::VertexLayout;
 use crate::renderable::Renderable;
+use crate::vertex::Vertex;
 
 const RECT_INDICES: [u32; 6] = [
     0, 1, 3,
@@ -15,24 +15,22 @@ const RECT_INDICES: [u32; 6] = [
 
 pub struct Rect {
     vao: VertexArray,
+    // Kept alive for the vertex array, which references it
+    _vb: VertexBuffer<Vertex>,
     ib: IndicesBuffer,
 }
 
 impl Rect {
-    pub fn new(vertices: [f32; 4 * 3], draw_mode: DrawMode) -> Rect {
+    pub fn new(vertices: [Vertex; 4], draw_mode: DrawMode) -> Rect {
         let mut vao = VertexArray::new();
         let ib = IndicesBuffer::new(&RECT_INDICES, DrawMode::STATIC);
         let vb = VertexBuffer::new(&vertices, draw_mode);
-        let mut layout = VertexBufferLayout::new();
 
-        layout.add_attribute(Type::Float, 3);
-        layout.add_attribute(Type::Float, 3);
-        layout.add_attribute(Type::Float, 2);
-
-        vao.add_buffer(&vb, &layout);
+        vao.add_buffer(&vb, &Vertex::layout());
 
         Rect {
             vao,
+            _vb: vb,
             ib,
         }
     }
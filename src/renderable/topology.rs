@@ -0,0 +1,31 @@
+use gl::types::GLenum;
+
+/// How the vertices of a mesh are assembled into primitives
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrimitiveTopology {
+    POINTS,
+    LINES,
+    LINE_STRIP,
+    LINE_LOOP,
+    TRIANGLES,
+    TRIANGLE_STRIP,
+    TRIANGLE_FAN,
+    /// Patches of the given number of vertices, which are processed by tessellation shaders
+    PATCHES(u32),
+}
+
+impl PrimitiveTopology {
+    pub fn to_gl_enum(&self) -> GLenum {
+        match self {
+            PrimitiveTopology::POINTS => gl::POINTS,
+            PrimitiveTopology::LINES => gl::LINES,
+            PrimitiveTopology::LINE_STRIP => gl::LINE_STRIP,
+            PrimitiveTopology::LINE_LOOP => gl::LINE_LOOP,
+            PrimitiveTopology::TRIANGLES => gl::TRIANGLES,
+            PrimitiveTopology::TRIANGLE_STRIP => gl::TRIANGLE_STRIP,
+            PrimitiveTopology::TRIANGLE_FAN => gl::TRIANGLE_FAN,
+            PrimitiveTopology::PATCHES(_) => gl::PATCHES,
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use std::ops::BitOr;
+
+use gl::types::GLbitfield;
+
+use crate::gl_call;
+use crate::shader::UnsupportedFeatureError;
+
+/// The kinds of memory accesses that must see the writes of shaders issued before a `memory_barrier`,
+/// e.g. image stores or shader storage buffer writes of a compute shader. Combined with `|`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryBarriers(GLbitfield);
+
+impl MemoryBarriers {
+    pub const VERTEX_ATTRIB_ARRAY: Self = Self(gl::VERTEX_ATTRIB_ARRAY_BARRIER_BIT);
+    pub const ELEMENT_ARRAY: Self = Self(gl::ELEMENT_ARRAY_BARRIER_BIT);
+    pub const UNIFORM: Self = Self(gl::UNIFORM_BARRIER_BIT);
+    pub const TEXTURE_FETCH: Self = Self(gl::TEXTURE_FETCH_BARRIER_BIT);
+    pub const SHADER_IMAGE_ACCESS: Self = Self(gl::SHADER_IMAGE_ACCESS_BARRIER_BIT);
+    pub const COMMAND: Self = Self(gl::COMMAND_BARRIER_BIT);
+    pub const PIXEL_BUFFER: Self = Self(gl::PIXEL_BUFFER_BARRIER_BIT);
+    pub const TEXTURE_UPDATE: Self = Self(gl::TEXTURE_UPDATE_BARRIER_BIT);
+    pub const BUFFER_UPDATE: Self = Self(gl::BUFFER_UPDATE_BARRIER_BIT);
+    pub const FRAMEBUFFER: Self = Self(gl::FRAMEBUFFER_BARRIER_BIT);
+    pub const ATOMIC_COUNTER: Self = Self(gl::ATOMIC_COUNTER_BARRIER_BIT);
+    pub const SHADER_STORAGE: Self = Self(gl::SHADER_STORAGE_BARRIER_BIT);
+    pub const ALL: Self = Self(gl::ALL_BARRIER_BITS);
+
+    pub fn bits(&self) -> GLbitfield {
+        self.0
+    }
+}
+
+impl BitOr for MemoryBarriers {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+/// Makes the writes of all previously issued shaders visible to the given kinds of later accesses
+pub fn memory_barrier(barriers: MemoryBarriers) -> Result<(), UnsupportedFeatureError> {
+    UnsupportedFeatureError::check("Memory barriers", 4, 2, "GL_ARB_shader_image_load_store")?;
+    gl_call!(gl::MemoryBarrier(barriers.0));
+    Ok(())
+}
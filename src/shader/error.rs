@@ -0,0 +1,462 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+
+use gl::types::GLuint;
+
+use crate::shader::{GlslType, ShaderType, SourceFile};
+use crate::util;
+
+/// Number of source lines shown before and after the offending line in a source excerpt
+const SOURCE_EXCERPT_CONTEXT_LINES: usize = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InfoLogSeverity {
+    Error,
+    Warning,
+    Info,
+}
+
+/// A single message of a shader or program info log.
+///
+/// Line and column are only available if the driver reports them in a known format.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InfoLogEntry {
+    pub severity: InfoLogSeverity,
+    /// The source string number, which is the index of the file for preprocessed sources
+    pub source: Option<u32>,
+    /// The name of the file the line refers to, for preprocessed sources
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub message: String,
+}
+
+#[derive(Debug)]
+pub enum ShaderError {
+    Compile {
+        stage: ShaderType,
+        entries: Vec<InfoLogEntry>,
+        log: String,
+        /// The source lines around the first error that reported a line number, prefixed with their line numbers
+        source_excerpt: Option<String>,
+    },
+    Link {
+        entries: Vec<InfoLogEntry>,
+        log: String,
+    },
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    /// An `#include` directive could not be resolved, see `Preprocessor`
+    Preprocess {
+        file: String,
+        line: u32,
+        message: String,
+    },
+    /// The current context does not support the shader stage
+    UnsupportedStage {
+        stage: ShaderType,
+        /// The OpenGL version that introduced the stage
+        required: (u32, u32),
+        /// The extension that provides the stage in earlier versions
+        extension: Option<&'static str>,
+        /// The version of the current context
+        version: (u32, u32),
+    },
+}
+
+/// An OpenGL feature, e.g. image load/store, is neither supported by the version of the current context nor as extension
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnsupportedFeatureError {
+    pub feature: &'static str,
+    /// The OpenGL version that introduced the feature
+    pub required: (u32, u32),
+    /// The extension that provides the feature in earlier versions
+    pub extension: &'static str,
+    /// The version of the current context
+    pub version: (u32, u32),
+}
+
+#[derive(Debug)]
+pub enum DispatchError {
+    /// The program has no compute shader
+    NoComputeShader {
+        program: GLuint,
+    },
+    /// The current context does not support compute shaders
+    UnsupportedFeature(UnsupportedFeatureError),
+}
+
+#[derive(Debug)]
+pub enum UniformError {
+    /// The value can not be assigned to the uniform's GLSL type
+    TypeMismatch {
+        name: String,
+        expected: GlslType,
+        actual: GlslType,
+    },
+    /// The value has more elements than the uniform array, starting at the named element
+    TooManyElements {
+        name: String,
+        size: u32,
+        count: usize,
+    },
+    /// The std140 size of the buffer's value does not match the size of the uniform block
+    BlockSizeMismatch {
+        name: String,
+        expected: usize,
+        actual: usize,
+    },
+    /// The uniform is of a kind the current context does not support, e.g. an image uniform
+    UnsupportedFeature(UnsupportedFeatureError),
+}
+
+impl UnsupportedFeatureError {
+    /// Checks that the current context is at least of the given version, or supports the given extension
+    pub(crate) fn check(feature: &'static str, major: u32, minor: u32, extension: &'static str) -> Result<(), Self> {
+        if util::is_supported(major, minor, extension) {
+            return Ok(());
+        }
+        Err(Self { feature, required: (major, minor), extension, version: util::gl_version() })
+    }
+}
+
+impl ShaderError {
+    /// Creates a compile error, whose entries refer to the given files if the source was preprocessed
+    pub(crate) fn compile(stage: ShaderType, log: String, src: &str, files: &[SourceFile]) -> Self {
+        let mut entries = parse_info_log(&log);
+        for entry in &mut entries {
+            entry.file = entry.source.and_then(|source| files.get(source as usize)).map(|file| file.name.clone());
+        }
+
+        let source_excerpt = entries.iter()
+            .filter(|entry| entry.severity == InfoLogSeverity::Error)
+            .find_map(|entry| Some((entry.source, entry.line?)))
+            .and_then(|(source, line)| {
+                if files.is_empty() {
+                    return source_excerpt(src, line);
+                }
+                source_excerpt(&files.get(source? as usize)?.source, line)
+            });
+
+        ShaderError::Compile {
+            stage,
+            entries,
+            log,
+            source_excerpt,
+        }
+    }
+
+    pub(crate) fn link(log: String) -> Self {
+        ShaderError::Link {
+            entries: parse_info_log(&log),
+            log,
+        }
+    }
+
+    pub fn entries(&self) -> &[InfoLogEntry] {
+        match self {
+            ShaderError::Compile { entries, .. } => entries,
+            ShaderError::Link { entries, .. } => entries,
+            ShaderError::Io { .. } | ShaderError::Preprocess { .. } | ShaderError::UnsupportedStage { .. } => &[],
+        }
+    }
+
+    /// The raw info log as reported by the driver
+    pub fn log(&self) -> &str {
+        match self {
+            ShaderError::Compile { log, .. } => log,
+            ShaderError::Link { log, .. } => log,
+            ShaderError::Io { .. } | ShaderError::Preprocess { .. } | ShaderError::UnsupportedStage { .. } => "",
+        }
+    }
+}
+
+impl Display for ShaderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShaderError::Compile { stage, source_excerpt, .. } => {
+                writeln!(f, "[Bowl] Could not compile {} shader:", stage)?;
+                write_entries(f, self.entries())?;
+                if let Some(source_excerpt) = source_excerpt {
+                    write!(f, "{}", source_excerpt)?;
+                }
+                Ok(())
+            }
+            ShaderError::Link { .. } => {
+                writeln!(f, "[Bowl] Could not link shader program:")?;
+                write_entries(f, self.entries())
+            }
+            ShaderError::Io { path, error } => {
+                write!(f, "[Bowl] Could not read shader source file {}: {}", path.display(), error)
+            }
+            ShaderError::Preprocess { file, line, message } => {
+                write!(f, "[Bowl] Could not preprocess shader source {}:{}: {}", file, line, message)
+            }
+            ShaderError::UnsupportedStage { stage, required, extension, version } => {
+                write!(f, "[Bowl] Could not create {} shader, which requires OpenGL {}.{}", stage, required.0, required.1)?;
+                if let Some(extension) = extension {
+                    write!(f, " or {}", extension)?;
+                }
+                write!(f, ", but the context has version {}.{}. Request a newer version with WindowBuilder::gl_version.", version.0, version.1)
+            }
+        }
+    }
+}
+
+impl Error for ShaderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ShaderError::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl Display for UniformError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UniformError::TypeMismatch { name, expected, actual } => {
+                write!(f, "[Bowl] Could not set uniform '{}' of type {} to a value of type {}", name, expected, actual)
+            }
+            UniformError::TooManyElements { name, size, count } => {
+                write!(f, "[Bowl] Could not set {} elements of uniform array '{}', which only has {} elements left", count, name, size)
+            }
+            UniformError::BlockSizeMismatch { name, expected, actual } => {
+                write!(f, "[Bowl] Could not bind a uniform buffer of {} bytes to uniform block '{}' of {} bytes", actual, name, expected)
+            }
+            UniformError::UnsupportedFeature(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl Error for UniformError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            UniformError::UnsupportedFeature(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl Display for DispatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DispatchError::NoComputeShader { program } => {
+                write!(f, "[Bowl] Could not dispatch shader program {}, which has no compute shader", program)
+            }
+            DispatchError::UnsupportedFeature(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl Error for DispatchError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DispatchError::UnsupportedFeature(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl Display for UnsupportedFeatureError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[Bowl] {} requires OpenGL {}.{} or {}, but the context has version {}.{}. Request a newer version with WindowBuilder::gl_version.",
+               self.feature, self.required.0, self.required.1, self.extension, self.version.0, self.version.1)
+    }
+}
+
+impl Error for UnsupportedFeatureError {}
+
+impl Display for InfoLogEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            InfoLogSeverity::Error => "error",
+            InfoLogSeverity::Warning => "warning",
+            InfoLogSeverity::Info => "info",
+        };
+
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file)?;
+        }
+
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "{}:{}: {}: {}", line, column, severity, self.message),
+            (Some(line), None) => write!(f, "{}: {}: {}", line, severity, self.message),
+            _ => write!(f, "{}: {}", severity, self.message),
+        }
+    }
+}
+
+fn write_entries(f: &mut Formatter<'_>, entries: &[InfoLogEntry]) -> std::fmt::Result {
+    for entry in entries {
+        writeln!(f, "  {}", entry)?;
+    }
+    Ok(())
+}
+
+/// Parses the info log formats of the common drivers:
+///
+/// - Mesa: `0:12(5): error: message`
+/// - NVIDIA: `0(12) : error C0000: message`
+/// - AMD, Intel and Apple: `ERROR: 0:12: message`
+pub(crate) fn parse_info_log(log: &str) -> Vec<InfoLogEntry> {
+    log.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(parse_info_log_line)
+        .collect()
+}
+
+fn parse_info_log_line(line: &str) -> InfoLogEntry {
+    // AMD, Intel and Apple prefix the location with the severity
+    if let Some((severity, rest)) = line.split_once(": ") {
+        if let Some(severity) = parse_severity(severity) {
+            if let Some((location, message)) = rest.split_once(": ") {
+                if let Some((source, line, column)) = parse_location(location) {
+                    return InfoLogEntry { severity, source: Some(source), file: None, line: Some(line), column, message: message.trim().to_string() };
+                }
+            }
+            return InfoLogEntry { severity, source: None, file: None, line: None, column: None, message: rest.trim().to_string() };
+        }
+    }
+
+    // Mesa and NVIDIA put the location first
+    if let Some((location, rest)) = line.split_once(": ") {
+        if let Some((source, line, column)) = parse_location(location) {
+            let (severity, message) = match rest.split_once(": ") {
+                Some((severity, message)) => match parse_severity(severity) {
+                    Some(severity) => (severity, message),
+                    None => (InfoLogSeverity::Error, rest),
+                },
+                None => (InfoLogSeverity::Error, rest),
+            };
+            return InfoLogEntry { severity, source: Some(source), file: None, line: Some(line), column, message: message.trim().to_string() };
+        }
+    }
+
+    InfoLogEntry {
+        severity: InfoLogSeverity::Info,
+        source: None,
+        file: None,
+        line: None,
+        column: None,
+        message: line.to_string(),
+    }
+}
+
+/// Parses severities like `error`, `ERROR` or `error C0000`
+fn parse_severity(severity: &str) -> Option<InfoLogSeverity> {
+    let severity = severity.split_whitespace().next()?.to_ascii_lowercase();
+    match severity.as_str() {
+        "error" | "fatal" => Some(InfoLogSeverity::Error),
+        "warning" => Some(InfoLogSeverity::Warning),
+        "info" | "note" => Some(InfoLogSeverity::Info),
+        _ => None,
+    }
+}
+
+/// Parses locations like `0:12(5)`, `0:12` or `0(12)` into the source string number, a line and an optional column
+fn parse_location(location: &str) -> Option<(u32, u32, Option<u32>)> {
+    let location = location.trim();
+
+    // NVIDIA: 0(12)
+    if let Some((source, rest)) = location.split_once('(') {
+        if !source.contains(':') {
+            let source = source.parse().ok()?;
+            let line = rest.strip_suffix(')')?.parse().ok()?;
+            return Some((source, line, None));
+        }
+    }
+
+    // Mesa, AMD, Intel and Apple: 0:12 or 0:12(5)
+    let (source, rest) = location.split_once(':')?;
+    let source = source.parse().ok()?;
+    match rest.split_once('(') {
+        Some((line, column)) => Some((source, line.parse().ok()?, Some(column.strip_suffix(')')?.parse().ok()?))),
+        None => Some((source, rest.parse().ok()?, None)),
+    }
+}
+
+fn source_excerpt(src: &str, line: u32) -> Option<String> {
+    let lines: Vec<&str> = src.lines().collect();
+    let line = line as usize;
+    if line == 0 || line > lines.len() {
+        return None;
+    }
+
+    let first = line.saturating_sub(SOURCE_EXCERPT_CONTEXT_LINES).max(1);
+    let last = (line + SOURCE_EXCERPT_CONTEXT_LINES).min(lines.len());
+
+    let mut excerpt = String::new();
+    for current in first..=last {
+        let marker = if current == line { ">" } else { " " };
+        excerpt.push_str(&format!("{} {:>4} | {}\n", marker, current, lines[current - 1]));
+    }
+    Some(excerpt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::InfoLogSeverity::{Error, Info, Warning};
+
+    /// A line of an info log with the expected severity, source, line, column and message
+    type InfoLogCase = (&'static str, InfoLogSeverity, Option<u32>, Option<u32>, Option<u32>, &'static str);
+
+    #[test]
+    fn parses_driver_info_log_lines() {
+        let cases: [InfoLogCase; 11] = [
+            // Mesa
+            ("0:12(5): error: `color' undeclared", Error, Some(0), Some(12), Some(5), "`color' undeclared"),
+            ("0:3(10): warning: extension `GL_ARB_foo' unsupported in fragment shader", Warning, Some(0), Some(3), Some(10),
+             "extension `GL_ARB_foo' unsupported in fragment shader"),
+            ("2:7(1): error: syntax error, unexpected '}'", Error, Some(2), Some(7), Some(1), "syntax error, unexpected '}'"),
+            ("error: vertex shader output `vColor' not written", Error, None, None, None, "vertex shader output `vColor' not written"),
+            // NVIDIA
+            ("0(12) : error C1008: undefined variable \"color\"", Error, Some(0), Some(12), None, "undefined variable \"color\""),
+            ("1(4) : warning C7022: unrecognized profile specifier \"foo\"", Warning, Some(1), Some(4), None,
+             "unrecognized profile specifier \"foo\""),
+            // AMD, Intel and Apple
+            ("ERROR: 0:12: 'color' : undeclared identifier", Error, Some(0), Some(12), None, "'color' : undeclared identifier"),
+            ("WARNING: 3:1: 'GL_ARB_foo' : extension not supported", Warning, Some(3), Some(1), None, "'GL_ARB_foo' : extension not supported"),
+            ("ERROR: 0:8: Use of undeclared identifier 'normal'", Error, Some(0), Some(8), None, "Use of undeclared identifier 'normal'"),
+            ("ERROR: 1 compilation errors.  No code generated.", Error, None, None, None, "1 compilation errors.  No code generated."),
+            // Anything else is kept as it is
+            ("Fragment shader failed to compile with the following errors:", Info, None, None, None,
+             "Fragment shader failed to compile with the following errors:"),
+        ];
+
+        for (log_line, severity, source, line, column, message) in cases {
+            let expected = InfoLogEntry { severity, source, file: None, line, column, message: message.to_string() };
+            assert_eq!(parse_info_log_line(log_line), expected, "{}", log_line);
+        }
+    }
+
+    #[test]
+    fn parses_locations() {
+        let cases = [
+            ("0:12(5)", Some((0, 12, Some(5)))),
+            ("3:1", Some((3, 1, None))),
+            ("0(12)", Some((0, 12, None))),
+            (" 2(7) ", Some((2, 7, None))),
+            ("0:x", None),
+            ("0(12", None),
+            ("ERROR", None),
+        ];
+
+        for (location, expected) in cases {
+            assert_eq!(parse_location(location), expected, "{}", location);
+        }
+    }
+
+    #[test]
+    fn skips_empty_lines() {
+        let log = "0:1(1): error: first\n\n  \n0:2(1): error: second\n";
+        let lines: Vec<Option<u32>> = parse_info_log(log).iter().map(|entry| entry.line).collect();
+        assert_eq!(lines, [Some(1), Some(2)]);
+    }
+}
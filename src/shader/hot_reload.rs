@@ -0,0 +1,194 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::{Rc, Weak};
+use std::time::SystemTime;
+
+use gl::types::GLuint;
+
+use crate::gl_call;
+use crate::leak_tracking::{self, GlObjectKind};
+use crate::shader::{uniform, Preprocessor, ProgramReflection, Shader, ShaderError, ShaderProgram, ShaderType, UniformValue};
+
+thread_local! {
+    /// All file backed shader programs that are still alive
+    static WATCHED_PROGRAMS: RefCell<Vec<Weak<RefCell<HotReloadState>>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Paths of included files with their modification time
+type IncludedFiles = Vec<(PathBuf, Option<SystemTime>)>;
+
+struct ShaderSourceFile {
+    r#type: ShaderType,
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+pub(crate) struct HotReloadState {
+    opengl_id: Rc<Cell<GLuint>>,
+    reflection: Rc<RefCell<ProgramReflection>>,
+    files: Vec<ShaderSourceFile>,
+    preprocessor: Option<Preprocessor>,
+    /// The files included by the preprocessed source files
+    included_files: IncludedFiles,
+    /// All uniform values that were set on the program, they are set again on the new program after every successful reload
+    uniform_values: HashMap<String, UniformValue>,
+    /// The binding point and std140 size of all uniform blocks that were bound to uniform buffers
+    uniform_blocks: HashMap<String, (u32, usize)>,
+}
+
+impl HotReloadState {
+    pub(crate) fn opengl_id(&self) -> Rc<Cell<GLuint>> {
+        self.opengl_id.clone()
+    }
+
+    pub(crate) fn reflection(&self) -> Rc<RefCell<ProgramReflection>> {
+        self.reflection.clone()
+    }
+
+    pub(crate) fn record_uniform(&mut self, name: &str, value: UniformValue) {
+        self.uniform_values.insert(name.to_string(), value);
+    }
+
+    pub(crate) fn record_uniform_block(&mut self, name: &str, binding: u32, size: usize) {
+        self.uniform_blocks.insert(name.to_string(), (binding, size));
+    }
+
+    pub(crate) fn paths(&self) -> Vec<&Path> {
+        self.files.iter().map(|file| file.path.as_path()).collect()
+    }
+
+    fn is_modified(&mut self) -> bool {
+        let mut modified = false;
+        for file in &mut self.files {
+            let current = modified_time(&file.path);
+            if current.is_some() && current != file.modified {
+                file.modified = current;
+                modified = true;
+            }
+        }
+        for (path, modified_before) in &mut self.included_files {
+            let current = modified_time(path);
+            if current.is_some() && current != *modified_before {
+                *modified_before = current;
+                modified = true;
+            }
+        }
+        modified
+    }
+
+    fn reload(&mut self) -> Result<(), ShaderError> {
+        let (shaders, included_files) = compile_files(&self.files, self.preprocessor.as_ref())?;
+        let new_program = ShaderProgram::link(shaders)?;
+        // Sources may include different files now
+        self.included_files = included_files;
+
+        gl_call!(gl::DeleteProgram(self.opengl_id.get()));
+        leak_tracking::track_deleted(GlObjectKind::Program, self.opengl_id.get());
+        self.opengl_id.set(new_program);
+
+        // Locations and types may have changed with the new sources
+        let reflection = ProgramReflection::query(new_program, self.files.iter().map(|file| file.r#type).collect());
+
+        // Restore all uniform values and uniform block bindings of the previous program
+        gl_call!(gl::UseProgram(new_program));
+        for (name, value) in &self.uniform_values {
+            if let Err(error) = value.upload(&reflection, name) {
+                eprintln!("{}", error);
+            }
+        }
+        for (name, (binding, size)) in &self.uniform_blocks {
+            if let Err(error) = uniform::bind_block(&reflection, name, *binding, *size) {
+                eprintln!("{}", error);
+            }
+        }
+
+        *self.reflection.borrow_mut() = reflection;
+
+        Ok(())
+    }
+}
+
+/// Loads, optionally preprocesses and compiles all shader source files and starts watching them and their included files
+/// for modifications
+pub(crate) fn load<P: AsRef<Path>>(files: &[(ShaderType, P)], preprocessor: Option<Preprocessor>) -> Result<Rc<RefCell<HotReloadState>>, ShaderError> {
+    let files: Vec<ShaderSourceFile> = files.iter()
+        .map(|(r#type, path)| ShaderSourceFile {
+            r#type: *r#type,
+            path: path.as_ref().to_path_buf(),
+            modified: modified_time(path.as_ref()),
+        })
+        .collect();
+
+    let (shaders, included_files) = compile_files(&files, preprocessor.as_ref())?;
+    let opengl_id = ShaderProgram::link(shaders)?;
+
+    let state = Rc::new(RefCell::new(HotReloadState {
+        opengl_id: Rc::new(Cell::new(opengl_id)),
+        reflection: Rc::new(RefCell::new(ProgramReflection::query(opengl_id, files.iter().map(|file| file.r#type).collect()))),
+        files,
+        preprocessor,
+        included_files,
+        uniform_values: HashMap::new(),
+        uniform_blocks: HashMap::new(),
+    }));
+
+    WATCHED_PROGRAMS.with(|programs| programs.borrow_mut().push(Rc::downgrade(&state)));
+
+    Ok(state)
+}
+
+/// Recompiles and relinks all file backed shader programs whose source files have been modified.
+///
+/// If a program fails to compile or link, the error is logged and the previous program is kept.
+pub(crate) fn reload_modified_programs() {
+    WATCHED_PROGRAMS.with(|programs| {
+        let mut programs = programs.borrow_mut();
+        programs.retain(|program| program.strong_count() > 0);
+
+        for program in programs.iter().filter_map(Weak::upgrade) {
+            let mut program = program.borrow_mut();
+            if !program.is_modified() {
+                continue;
+            }
+
+            if let Err(error) = program.reload() {
+                eprintln!("[Bowl] Could not reload shader program from {:?}, keeping the previous program:\n{}", program.paths(), error);
+            }
+        }
+    });
+}
+
+/// Compiles the source files, returning the shaders and the files included by them with their modification time
+fn compile_files(files: &[ShaderSourceFile], preprocessor: Option<&Preprocessor>) -> Result<(Vec<Shader>, IncludedFiles), ShaderError> {
+    let mut shaders = Vec::new();
+    let mut included_files = Vec::new();
+
+    for file in files {
+        let shader = match preprocessor {
+            Some(preprocessor) => {
+                let src = preprocessor.process_file(&file.path)?;
+                included_files.extend(src.files.iter()
+                    .skip(1)
+                    .filter_map(|included| included.path.clone())
+                    .map(|path| {
+                        let modified = modified_time(&path);
+                        (path, modified)
+                    }));
+                Shader::from_preprocessed(file.r#type, &src)?
+            }
+            None => {
+                let src = fs::read_to_string(&file.path).map_err(|error| ShaderError::Io { path: file.path.clone(), error })?;
+                Shader::new(file.r#type, &src)?
+            }
+        };
+        shaders.push(shader);
+    }
+
+    Ok((shaders, included_files))
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
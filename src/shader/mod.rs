@@ -0,0 +1,385 @@
+use std::cell::{Cell, Ref, RefCell};
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::ptr::null_mut;
+use std::rc::Rc;
+
+use gl::types::{GLchar, GLenum, GLint, GLsizei, GLuint};
+use glam::{IVec2, IVec3, IVec4, Mat4, UVec2, UVec3, UVec4, Vec2, Vec3, Vec4};
+
+use crate::data::std140::Std140;
+use crate::data::uniform_buffer::UniformBuffer;
+use crate::gl_call;
+use crate::leak_tracking::{self, GlObjectKind};
+use crate::texture::{ImageAccess, Texture2D};
+use crate::util;
+
+use hot_reload::HotReloadState;
+
+pub use compute::{memory_barrier, MemoryBarriers};
+pub use error::{DispatchError, InfoLogEntry, InfoLogSeverity, ShaderError, UniformError, UnsupportedFeatureError};
+pub use preprocessor::{PreprocessedSource, Preprocessor, SourceFile};
+pub use reflection::{ActiveAttribute, ActiveUniform, ActiveUniformBlock, GlslType, ProgramReflection};
+pub use uniform::{Uniform, UniformComponents, UniformElement};
+pub(crate) use hot_reload::reload_modified_programs;
+pub(crate) use uniform::UniformValue;
+
+mod compute;
+mod error;
+mod hot_reload;
+mod preprocessor;
+mod reflection;
+mod uniform;
+
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShaderType {
+    VERTEX,
+    FRAGMENT,
+    GEOMETRY,
+    TESS_CONTROL,
+    TESS_EVALUATION,
+    COMPUTE,
+}
+
+pub struct Shader {
+    pub r#type: ShaderType,
+    pub opengl_id: GLuint,
+}
+
+pub struct ShaderProgram {
+    pub shaders: Vec<String>,
+    // Shared with the hot reload state, which replaces the program when its source files are modified
+    opengl_id: Rc<Cell<GLuint>>,
+    reflection: Rc<RefCell<ProgramReflection>>,
+    hot_reload: Option<Rc<RefCell<HotReloadState>>>,
+}
+
+impl Shader {
+    pub fn new(r#type: ShaderType, src: &str) -> Result<Self, ShaderError> {
+        Ok(Self {
+            r#type,
+            opengl_id: Self::compile(r#type, src, &[])?,
+        })
+    }
+
+    /// Preprocesses the source, e.g. to resolve its `#include`s, and compiles it.
+    /// Compile errors refer to the lines of the original files.
+    pub fn preprocessed(r#type: ShaderType, name: &str, src: &str, preprocessor: &Preprocessor) -> Result<Self, ShaderError> {
+        Self::from_preprocessed(r#type, &preprocessor.process(name, src)?)
+    }
+
+    pub fn from_preprocessed(r#type: ShaderType, src: &PreprocessedSource) -> Result<Self, ShaderError> {
+        Ok(Self {
+            r#type,
+            opengl_id: Self::compile(r#type, &src.source, &src.files)?,
+        })
+    }
+
+    fn compile(r#type: ShaderType, src: &str, files: &[SourceFile]) -> Result<GLuint, ShaderError> {
+        if !r#type.is_supported() {
+            let (major, minor, extension) = r#type.requirement();
+            return Err(ShaderError::UnsupportedStage {
+                stage: r#type,
+                required: (major, minor),
+                extension,
+                version: util::gl_version(),
+            });
+        }
+
+        let shader = gl_call!(gl::CreateShader(r#type.to_gl()));
+
+        gl_call!(
+            gl::ShaderSource(shader, 1, &(src.as_bytes().as_ptr().cast()), &src.len().try_into().unwrap()),
+            gl::CompileShader(shader),
+        );
+
+        let mut success: i32 = 0;
+
+        gl_call!(gl::GetShaderiv(shader, gl::COMPILE_STATUS, &mut success));
+        if success == 0 {
+            let log = read_info_log(shader, gl::GetShaderiv, gl::GetShaderInfoLog);
+            gl_call!(gl::DeleteShader(shader));
+            return Err(ShaderError::compile(r#type, log, src, files));
+        }
+
+        leak_tracking::track_created(GlObjectKind::Shader, shader);
+        Ok(shader)
+    }
+}
+
+impl ShaderProgram {
+    pub fn new(shaders: Vec<Shader>) -> Result<Self, ShaderError> {
+        let stages = shaders.iter().map(|shader| shader.r#type).collect();
+        let opengl_id = Self::link(shaders)?;
+
+        Ok(Self {
+            shaders: Vec::new(),
+            opengl_id: Rc::new(Cell::new(opengl_id)),
+            reflection: Rc::new(RefCell::new(ProgramReflection::query(opengl_id, stages))),
+            hot_reload: None,
+        })
+    }
+
+    /// Creates a shader program from shader source files.
+    ///
+    /// The source files are watched while the window is running. When one of them is modified, the program
+    /// is recompiled and relinked, and all uniform values that were set on the program are set again.
+    /// If the modified sources fail to compile or link, the error is logged and the previous program is kept.
+    pub fn from_files<P: AsRef<Path>>(files: &[(ShaderType, P)]) -> Result<Self, ShaderError> {
+        Ok(Self::from_hot_reload(hot_reload::load(files, None)?))
+    }
+
+    /// Creates a shader program from shader source files, which are preprocessed before they are compiled.
+    ///
+    /// Like with `from_files`, the source files are watched while the window is running, as are all files on disk
+    /// they include.
+    pub fn from_files_preprocessed<P: AsRef<Path>>(files: &[(ShaderType, P)], preprocessor: &Preprocessor) -> Result<Self, ShaderError> {
+        Ok(Self::from_hot_reload(hot_reload::load(files, Some(preprocessor.clone()))?))
+    }
+
+    fn from_hot_reload(hot_reload: Rc<RefCell<HotReloadState>>) -> Self {
+        let opengl_id = hot_reload.borrow().opengl_id();
+        let reflection = hot_reload.borrow().reflection();
+
+        Self {
+            shaders: Vec::new(),
+            opengl_id,
+            reflection,
+            hot_reload: Some(hot_reload),
+        }
+    }
+
+    fn link(shaders: Vec<Shader>) -> Result<GLuint, ShaderError> {
+        let gl_program = gl_call!(gl::CreateProgram());
+
+        for shader in &shaders {
+            let shader = shader.opengl_id;
+            gl_call!(gl::AttachShader(gl_program, shader));
+        }
+
+        gl_call!(gl::LinkProgram(gl_program));
+
+        for shader in shaders {
+            gl_call!(gl::DetachShader(gl_program, shader.opengl_id));
+        }
+
+        let mut success: i32 = 0;
+
+        gl_call!(gl::GetProgramiv(gl_program, gl::LINK_STATUS, &mut success));
+        if success == 0 {
+            let log = read_info_log(gl_program, gl::GetProgramiv, gl::GetProgramInfoLog);
+            gl_call!(gl::DeleteProgram(gl_program));
+            return Err(ShaderError::link(log));
+        }
+
+        leak_tracking::track_created(GlObjectKind::Program, gl_program);
+        Ok(gl_program)
+    }
+
+    /// The OpenGL name of the program, which changes when the program is hot reloaded
+    pub fn opengl_id(&self) -> GLuint {
+        self.opengl_id.get()
+    }
+
+    pub fn bind(&self) {
+        gl_call!(gl::UseProgram(self.opengl_id()));
+    }
+
+    /// The active uniforms and attributes of this program. After a hot reload, they describe the new program.
+    pub fn reflection(&self) -> Ref<'_, ProgramReflection> {
+        self.reflection.borrow()
+    }
+
+    /// The location of the uniform with the given name, or `None` if it is not used by the program
+    pub fn uniform_location(&self, name: &str) -> Option<GLint> {
+        self.reflection().uniform_location(name)
+    }
+
+    /// Sets the uniform with the given name of the bound program, or the uniform array elements starting at the named element.
+    ///
+    /// The value is checked against the reflected type and array size of the uniform. Uniforms that are not used by
+    /// the program are ignored.
+    pub fn set_uniform<U: Uniform>(&self, name: &str, value: U) -> Result<(), UniformError> {
+        uniform::upload(&self.reflection(), name, U::TYPE, value.count(), &value.components())?;
+
+        if let Some(hot_reload) = &self.hot_reload {
+            hot_reload.borrow_mut().record_uniform(name, UniformValue::new(&value));
+        }
+        Ok(())
+    }
+
+    #[deprecated(note = "use `set_uniform`, which checks the value against the type of the uniform")]
+    pub fn set_uniform_float(&self, name: &str, data: Vec<f32>) {
+        log_uniform_error(match data.len() {
+            1 => self.set_uniform(name, data[0]),
+            2 => self.set_uniform(name, Vec2::from_slice(&data)),
+            3 => self.set_uniform(name, Vec3::from_slice(&data)),
+            4 => self.set_uniform(name, Vec4::from_slice(&data)),
+            // Other lengths are uploaded as array, so they are reported as mismatch unless the uniform is an array
+            _ => self.set_uniform(name, &data[..]),
+        });
+    }
+
+    #[deprecated(note = "use `set_uniform`, which checks the value against the type of the uniform")]
+    pub fn set_uniform_int(&self, name: &str, data: Vec<i32>) {
+        log_uniform_error(match data.len() {
+            1 => self.set_uniform(name, data[0]),
+            2 => self.set_uniform(name, IVec2::from_slice(&data)),
+            3 => self.set_uniform(name, IVec3::from_slice(&data)),
+            4 => self.set_uniform(name, IVec4::from_slice(&data)),
+            _ => self.set_uniform(name, &data[..]),
+        });
+    }
+
+    #[deprecated(note = "use `set_uniform`, which checks the value against the type of the uniform")]
+    pub fn set_uniform_uint(&self, name: &str, data: Vec<u32>) {
+        log_uniform_error(match data.len() {
+            1 => self.set_uniform(name, data[0]),
+            2 => self.set_uniform(name, UVec2::from_slice(&data)),
+            3 => self.set_uniform(name, UVec3::from_slice(&data)),
+            4 => self.set_uniform(name, UVec4::from_slice(&data)),
+            _ => self.set_uniform(name, &data[..]),
+        });
+    }
+
+    #[deprecated(note = "use `set_uniform`, which checks the value against the type of the uniform")]
+    pub fn set_uniform_mat4(&self, name: &str, data: Mat4) {
+        log_uniform_error(self.set_uniform(name, data));
+    }
+
+    /// Assigns the binding point of the uniform buffer to the uniform block with the given name, after checking that
+    /// the std140 size of the buffer's value matches the size of the block. Blocks that are not used by the program are ignored.
+    ///
+    /// The block must be declared with `layout(std140)`. With the default `shared` layout, the driver chooses the
+    /// offsets of the members, which can differ from std140 even if the sizes match.
+    ///
+    /// The binding is kept when the program is reloaded.
+    pub fn bind_uniform_block<T: Std140>(&self, block_name: &str, buffer: &UniformBuffer<T>) -> Result<(), UniformError> {
+        uniform::bind_block(&self.reflection(), block_name, buffer.binding(), T::SIZE)?;
+
+        if let Some(hot_reload) = &self.hot_reload {
+            hot_reload.borrow_mut().record_uniform_block(block_name, buffer.binding(), T::SIZE);
+        }
+        Ok(())
+    }
+
+    /// Binds this program and runs its compute shader with the given number of work groups in each dimension.
+    ///
+    /// Writes of the compute shader are only visible to later commands after a matching `memory_barrier`.
+    pub fn dispatch(&self, x: u32, y: u32, z: u32) -> Result<(), DispatchError> {
+        UnsupportedFeatureError::check("Compute shader dispatch", 4, 3, "GL_ARB_compute_shader").map_err(DispatchError::UnsupportedFeature)?;
+        if self.reflection().work_group_size().is_none() {
+            return Err(DispatchError::NoComputeShader { program: self.opengl_id() });
+        }
+
+        self.bind();
+        gl_call!(gl::DispatchCompute(x, y, z));
+        Ok(())
+    }
+
+    /// Binds the texture to the given texture unit and assigns that unit to the sampler uniform with the given name.
+    ///
+    /// The program must be bound.
+    pub fn try_bind_texture(&self, sampler_name: &str, texture: &Texture2D, unit: u32) -> Result<(), UniformError> {
+        texture.bind(unit);
+        self.set_uniform(sampler_name, unit as i32)
+    }
+
+    #[deprecated(note = "use `try_bind_texture`, which returns errors instead of logging them")]
+    pub fn bind_texture(&self, sampler_name: &str, texture: &Texture2D, unit: u32) {
+        log_uniform_error(self.try_bind_texture(sampler_name, texture, unit));
+    }
+
+    /// Binds the texture to the given image unit for image load/store and assigns that unit to the image uniform
+    /// with the given name.
+    ///
+    /// The program must be bound.
+    pub fn bind_image(&self, image_name: &str, texture: &Texture2D, unit: u32, access: ImageAccess) -> Result<(), UniformError> {
+        texture.bind_image(unit, access).map_err(UniformError::UnsupportedFeature)?;
+        self.set_uniform(image_name, unit as i32)
+    }
+}
+
+/// Logs errors of the deprecated uniform functions, which do not return them
+fn log_uniform_error(result: Result<(), UniformError>) {
+    if let Err(error) = result {
+        eprintln!("{}", error);
+    }
+}
+
+impl Drop for Shader {
+    fn drop(&mut self) {
+        // Shaders attached to a program are only flagged for deletion, linked programs do not need them anymore
+        gl_call!(gl::DeleteShader(self.opengl_id));
+        leak_tracking::track_deleted(GlObjectKind::Shader, self.opengl_id);
+    }
+}
+
+impl Drop for ShaderProgram {
+    fn drop(&mut self) {
+        gl_call!(gl::DeleteProgram(self.opengl_id()));
+        leak_tracking::track_deleted(GlObjectKind::Program, self.opengl_id());
+    }
+}
+
+impl ShaderType {
+    fn to_gl(&self) -> GLenum {
+        match self {
+            ShaderType::VERTEX => gl::VERTEX_SHADER,
+            ShaderType::FRAGMENT => gl::FRAGMENT_SHADER,
+            ShaderType::GEOMETRY => gl::GEOMETRY_SHADER,
+            ShaderType::TESS_CONTROL => gl::TESS_CONTROL_SHADER,
+            ShaderType::TESS_EVALUATION => gl::TESS_EVALUATION_SHADER,
+            ShaderType::COMPUTE => gl::COMPUTE_SHADER,
+        }
+    }
+
+    /// The OpenGL version that introduced this stage, and the extension that provides it in earlier versions
+    fn requirement(&self) -> (u32, u32, Option<&'static str>) {
+        match self {
+            ShaderType::VERTEX | ShaderType::FRAGMENT => (2, 0, None),
+            ShaderType::GEOMETRY => (3, 2, None),
+            ShaderType::TESS_CONTROL | ShaderType::TESS_EVALUATION => (4, 0, Some("GL_ARB_tessellation_shader")),
+            ShaderType::COMPUTE => (4, 3, Some("GL_ARB_compute_shader")),
+        }
+    }
+
+    /// Whether the current OpenGL context supports shaders of this stage
+    pub fn is_supported(&self) -> bool {
+        match self.requirement() {
+            (major, minor, Some(extension)) => util::is_supported(major, minor, extension),
+            (major, minor, None) => util::gl_version() >= (major, minor),
+        }
+    }
+}
+
+impl Display for ShaderType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShaderType::VERTEX => write!(f, "vertex"),
+            ShaderType::FRAGMENT => write!(f, "fragment"),
+            ShaderType::GEOMETRY => write!(f, "geometry"),
+            ShaderType::TESS_CONTROL => write!(f, "tessellation control"),
+            ShaderType::TESS_EVALUATION => write!(f, "tessellation evaluation"),
+            ShaderType::COMPUTE => write!(f, "compute"),
+        }
+    }
+}
+
+/// Reads the info log of a shader or program object
+fn read_info_log(opengl_id: GLuint,
+                 get_parameter: unsafe fn(GLuint, GLenum, *mut GLint),
+                 get_info_log: unsafe fn(GLuint, GLsizei, *mut GLsizei, *mut GLchar)) -> String {
+    let mut log_length: GLint = 0;
+    gl_call!(get_parameter(opengl_id, gl::INFO_LOG_LENGTH, &mut log_length));
+
+    let mut log: Vec<u8> = vec![0; log_length.max(1) as usize];
+    gl_call!(get_info_log(opengl_id, log.len() as GLsizei, null_mut(), log.as_mut_ptr() as *mut GLchar));
+
+    // The log is null terminated
+    if let Some(end) = log.iter().position(|byte| *byte == 0) {
+        log.truncate(end);
+    }
+    String::from_utf8_lossy(&log).into_owned()
+}
\ No newline at end of file
@@ -0,0 +1,408 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::shader::ShaderError;
+
+/// Preprocesses GLSL sources before they are compiled:
+///
+/// - `#include "name"` and `#include <name>` are replaced by the included file. Quoted names are first searched
+///   relative to the including file, then all names are searched in the embedded sources and the include directories.
+///   Files containing `#pragma once` are only included once. Includes are expanded regardless of surrounding
+///   conditionals.
+/// - The `#version` directive is moved to the first line, followed by the configured `#define`s.
+///   Sources without `#version` get the configured default version.
+/// - `#line` directives are inserted around included files, so compile errors refer to the line in the original file.
+///   The source string number of a `#line` directive is the index of the file in `PreprocessedSource::files`.
+///   Before GLSL 3.00, `#line N` numbers the following line N + 1 instead of N, which is accounted for.
+#[derive(Clone, Debug, Default)]
+pub struct Preprocessor {
+    version: Option<String>,
+    defines: Vec<(String, String)>,
+    /// Embedded sources by name, searched before the include directories
+    sources: HashMap<String, String>,
+    include_directories: Vec<PathBuf>,
+}
+
+/// A shader source after preprocessing, with the files it was assembled from
+#[derive(Clone, Debug)]
+pub struct PreprocessedSource {
+    pub source: String,
+    /// The root file followed by all included files, in the order they were first included
+    pub files: Vec<SourceFile>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SourceFile {
+    pub name: String,
+    pub source: String,
+    /// The path of files that were read from disk
+    pub path: Option<PathBuf>,
+}
+
+/// Identifies an included file, to detect include cycles and repeated includes of `#pragma once` files
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum FileKey {
+    Embedded(String),
+    Path(PathBuf),
+}
+
+struct Expansion {
+    source: String,
+    files: Vec<SourceFile>,
+    /// The files that are currently being expanded, from the root file to the innermost include
+    stack: Vec<FileKey>,
+    once: HashSet<FileKey>,
+    /// Subtracted from the line numbers of `#line` directives, 1 for versions that number the following line N + 1
+    line_offset: u32,
+}
+
+impl Preprocessor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The version for sources without `#version` directive, e.g. `"330 core"`
+    pub fn version(mut self, version: &str) -> Self {
+        self.version = Some(version.to_string());
+        return self;
+    }
+
+    /// Adds `#define name value` after the `#version` directive
+    pub fn define(mut self, name: &str, value: impl Display) -> Self {
+        self.defines.push((name.to_string(), value.to_string()));
+        return self;
+    }
+
+    /// Adds an embedded source that can be included by the given name, e.g. loaded with `include_str!`
+    pub fn include_source(mut self, name: &str, source: &str) -> Self {
+        self.sources.insert(name.to_string(), source.to_string());
+        return self;
+    }
+
+    /// Adds a directory that included files are searched in, after the embedded sources
+    pub fn include_directory<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.include_directories.push(path.as_ref().to_path_buf());
+        return self;
+    }
+
+    /// Preprocesses a source, whose name is used in errors. Quoted includes are not searched relative to it.
+    pub fn process(&self, name: &str, src: &str) -> Result<PreprocessedSource, ShaderError> {
+        self.process_root(SourceFile { name: name.to_string(), source: src.to_string(), path: None }, FileKey::Embedded(name.to_string()))
+    }
+
+    /// Reads and preprocesses a source file
+    pub fn process_file<P: AsRef<Path>>(&self, path: P) -> Result<PreprocessedSource, ShaderError> {
+        let path = path.as_ref();
+        // The key is canonicalized like the keys of included files, so includes of the root file are recognized
+        let (root, key) = read_file(path.to_path_buf()).map_err(|error| ShaderError::Io { path: path.to_path_buf(), error })?;
+
+        self.process_root(root, key)
+    }
+
+    fn process_root(&self, root: SourceFile, key: FileKey) -> Result<PreprocessedSource, ShaderError> {
+        let version = root.source.lines()
+            .find_map(|line| directive(line, "version"))
+            .map(str::to_string)
+            .or_else(|| self.version.clone());
+
+        let mut header = String::new();
+        if let Some(version) = &version {
+            header.push_str(&format!("#version {}\n", version));
+        }
+        for (name, value) in &self.defines {
+            header.push_str(&format!("#define {} {}\n", name, value));
+        }
+
+        let mut expansion = Expansion {
+            source: header,
+            files: Vec::new(),
+            stack: Vec::new(),
+            once: HashSet::new(),
+            line_offset: line_directive_offset(version.as_deref()),
+        };
+        expansion.push_line_directive(1, 0);
+        self.expand(&mut expansion, root, key)?;
+
+        Ok(PreprocessedSource {
+            source: expansion.source,
+            files: expansion.files,
+        })
+    }
+
+    /// Appends the file to the expanded source, replacing its includes by the included files
+    fn expand(&self, expansion: &mut Expansion, file: SourceFile, key: FileKey) -> Result<(), ShaderError> {
+        let index = expansion.files.len();
+        expansion.files.push(file.clone());
+        expansion.stack.push(key.clone());
+
+        for (line_index, line) in file.source.lines().enumerate() {
+            let line_number = line_index as u32 + 1;
+
+            // The version directive has been moved to the first line
+            if directive(line, "version").is_some() {
+                expansion.source.push('\n');
+                continue;
+            }
+
+            if directive(line, "pragma").is_some_and(|pragma| pragma == "once") {
+                expansion.once.insert(key.clone());
+                expansion.source.push('\n');
+                continue;
+            }
+
+            let Some(include) = directive(line, "include") else {
+                expansion.source.push_str(line);
+                expansion.source.push('\n');
+                continue;
+            };
+
+            let error = |message: String| ShaderError::Preprocess { file: file.name.clone(), line: line_number, message };
+
+            let (name, quoted) = parse_include_name(include)
+                .ok_or_else(|| error(format!("Expected \"name\" or <name> after #include, found '{}'", include)))?;
+            let (included, included_key) = self.resolve(name, quoted, file.path.as_deref())
+                .ok_or_else(|| error(format!("Could not find included file '{}'", name)))?
+                .map_err(|io_error| error(format!("Could not read included file '{}': {}", name, io_error)))?;
+
+            // Files with `#pragma once` are skipped, even if they are still being expanded
+            if !expansion.once.contains(&included_key) {
+                if expansion.stack.contains(&included_key) {
+                    return Err(error(format!("Including '{}' would create an include cycle", name)));
+                }
+
+                expansion.push_line_directive(1, expansion.files.len());
+                self.expand(expansion, included, included_key)?;
+            }
+            expansion.push_line_directive(line_number + 1, index);
+        }
+
+        expansion.stack.pop();
+        Ok(())
+    }
+
+    /// Finds the included file: relative to the including file for quoted names, then in the embedded sources,
+    /// then in the include directories
+    fn resolve(&self, name: &str, quoted: bool, including_path: Option<&Path>) -> Option<Result<(SourceFile, FileKey), std::io::Error>> {
+        let relative = including_path
+            .filter(|_| quoted)
+            .and_then(Path::parent)
+            .map(|directory| directory.join(name));
+
+        if let Some(path) = relative.filter(|path| path.is_file()) {
+            return Some(read_file(path));
+        }
+
+        if let Some(source) = self.sources.get(name) {
+            let file = SourceFile { name: name.to_string(), source: source.clone(), path: None };
+            return Some(Ok((file, FileKey::Embedded(name.to_string()))));
+        }
+
+        self.include_directories.iter()
+            .map(|directory| directory.join(name))
+            .find(|path| path.is_file())
+            .map(read_file)
+    }
+}
+
+impl Expansion {
+    /// Appends a `#line` directive, after which the following line has the given number in the given file
+    fn push_line_directive(&mut self, line: u32, file_index: usize) {
+        self.source.push_str(&format!("#line {} {}\n", line - self.line_offset, file_index));
+    }
+}
+
+/// GLSL versions before 3.00, i.e. before 3.30 and GLSL ES 3.00, number the line after `#line N` as N + 1.
+/// Sources without version are GLSL 1.10.
+fn line_directive_offset(version: Option<&str>) -> u32 {
+    let number = version
+        .and_then(|version| version.split_whitespace().next())
+        .and_then(|number| number.parse::<u32>().ok())
+        .unwrap_or(110);
+    u32::from(number < 300)
+}
+
+fn read_file(path: PathBuf) -> Result<(SourceFile, FileKey), std::io::Error> {
+    let source = fs::read_to_string(&path)?;
+    // The same file may be reached through different relative paths
+    let key = FileKey::Path(path.canonicalize().unwrap_or_else(|_| path.clone()));
+
+    Ok((SourceFile { name: path.display().to_string(), source, path: Some(path) }, key))
+}
+
+/// The arguments of the given preprocessor directive if the line is one, e.g. `330 core` of `#version 330 core`
+fn directive<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    let rest = line.trim_start().strip_prefix('#')?.trim_start().strip_prefix(name)?;
+
+    // Directives are separated from their arguments by whitespace, unlike e.g. `#included`
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some(rest.trim())
+}
+
+/// Parses `"name"` or `<name>` into the name and whether it was quoted
+fn parse_include_name(include: &str) -> Option<(&str, bool)> {
+    if let Some(name) = include.strip_prefix('"').and_then(|rest| rest.strip_suffix('"')) {
+        return Some((name, true));
+    }
+    let name = include.strip_prefix('<')?.strip_suffix('>')?;
+    Some((name, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    fn preprocess(preprocessor: &Preprocessor, src: &str) -> String {
+        preprocessor.process("root", src).expect("Could not preprocess source").source
+    }
+
+    fn preprocess_error(preprocessor: &Preprocessor, src: &str) -> (String, u32, String) {
+        match preprocessor.process("root", src) {
+            Err(ShaderError::Preprocess { file, line, message }) => (file, line, message),
+            result => panic!("Expected a preprocess error, got {:?}", result),
+        }
+    }
+
+    /// A new empty directory in the temporary directory
+    fn temporary_directory(name: &str) -> PathBuf {
+        let directory = env::temp_dir().join(format!("bowl-preprocessor-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).expect("Could not create temporary directory");
+        directory
+    }
+
+    #[test]
+    fn parses_directives() {
+        assert_eq!(directive("#version 330 core", "version"), Some("330 core"));
+        assert_eq!(directive("  #  include \"a.glsl\"  ", "include"), Some("\"a.glsl\""));
+        assert_eq!(directive("#pragma once", "pragma"), Some("once"));
+        assert_eq!(directive("#version", "version"), Some(""));
+        assert_eq!(directive("#included \"a.glsl\"", "include"), None);
+        assert_eq!(directive("// #include \"a.glsl\"", "include"), None);
+        assert_eq!(directive("#define VERSION 2", "version"), None);
+    }
+
+    #[test]
+    fn parses_include_names() {
+        assert_eq!(parse_include_name("\"lights.glsl\""), Some(("lights.glsl", true)));
+        assert_eq!(parse_include_name("<common/math.glsl>"), Some(("common/math.glsl", false)));
+        assert_eq!(parse_include_name("lights.glsl"), None);
+        assert_eq!(parse_include_name("\"lights.glsl>"), None);
+        assert_eq!(parse_include_name("<lights.glsl"), None);
+    }
+
+    #[test]
+    fn moves_version_to_first_line_followed_by_defines() {
+        let preprocessor = Preprocessor::new().version("330 core").define("LIGHTS", 4).define("SCALE", 2.5);
+        let source = preprocess(&preprocessor, "// comment\n#version 430 core\nvoid main() {}\n");
+
+        assert_eq!(source, "#version 430 core\n#define LIGHTS 4\n#define SCALE 2.5\n#line 1 0\n// comment\n\nvoid main() {}\n");
+    }
+
+    #[test]
+    fn uses_default_version_without_version_directive() {
+        let preprocessor = Preprocessor::new().version("330 core");
+        assert_eq!(preprocess(&preprocessor, "void main() {}"), "#version 330 core\n#line 1 0\nvoid main() {}\n");
+    }
+
+    #[test]
+    fn inserts_line_directives_around_includes() {
+        let preprocessor = Preprocessor::new()
+            .version("330 core")
+            .include_source("outer", "#include <inner>\nfloat outer() { return inner(); }")
+            .include_source("inner", "float inner() { return 1.0; }");
+        let source = preprocess(&preprocessor, "// root\n#include \"outer\"\nvoid main() {}");
+
+        assert_eq!(source, "#version 330 core\n#line 1 0\n// root\n\
+                            #line 1 1\n\
+                            #line 1 2\nfloat inner() { return 1.0; }\n\
+                            #line 2 1\nfloat outer() { return inner(); }\n\
+                            #line 3 0\nvoid main() {}\n");
+
+        let files = preprocessor.process("root", "#include \"outer\"").expect("Could not preprocess source").files;
+        let names: Vec<&str> = files.iter().map(|file| file.name.as_str()).collect();
+        assert_eq!(names, ["root", "outer", "inner"]);
+    }
+
+    #[test]
+    fn offsets_line_directives_before_glsl_300() {
+        let preprocessor = Preprocessor::new().include_source("inner", "float inner() { return 1.0; }");
+
+        assert_eq!(preprocess(&preprocessor.clone().version("150"), "#include <inner>\nvoid main() {}"),
+                   "#version 150\n#line 0 0\n#line 0 1\nfloat inner() { return 1.0; }\n#line 1 0\nvoid main() {}\n");
+        assert_eq!(preprocess(&preprocessor.clone().version("300 es"), "#include <inner>\nvoid main() {}"),
+                   "#version 300 es\n#line 1 0\n#line 1 1\nfloat inner() { return 1.0; }\n#line 2 0\nvoid main() {}\n");
+        // Sources without version are GLSL 1.10
+        assert_eq!(preprocess(&preprocessor, "void main() {}"), "#line 0 0\nvoid main() {}\n");
+    }
+
+    #[test]
+    fn detects_include_cycles() {
+        let preprocessor = Preprocessor::new()
+            .include_source("a", "#include <b>")
+            .include_source("b", "\n#include <a>");
+
+        assert_eq!(preprocess_error(&preprocessor, "#include <a>"),
+                   ("b".to_string(), 2, "Including 'a' would create an include cycle".to_string()));
+    }
+
+    #[test]
+    fn reports_missing_includes() {
+        assert_eq!(preprocess_error(&Preprocessor::new(), "\n#include <missing>"),
+                   ("root".to_string(), 2, "Could not find included file 'missing'".to_string()));
+        assert_eq!(preprocess_error(&Preprocessor::new(), "#include missing"),
+                   ("root".to_string(), 1, "Expected \"name\" or <name> after #include, found 'missing'".to_string()));
+    }
+
+    #[test]
+    fn includes_pragma_once_files_once() {
+        let preprocessor = Preprocessor::new()
+            .version("330 core")
+            .include_source("once", "#pragma once\nfloat once() { return 1.0; }")
+            .include_source("twice", "float twice() { return 2.0; }");
+        let source = preprocess(&preprocessor, "#include <once>\n#include <once>\n#include <twice>\n#include <twice>");
+
+        assert_eq!(source.matches("float once()").count(), 1);
+        assert_eq!(source.matches("float twice()").count(), 2);
+        // The skipped include still restores the line number of the including file
+        assert!(source.contains("#line 2 0\n#line 3 0\n"), "{}", source);
+    }
+
+    #[test]
+    fn includes_pragma_once_root_file_once() {
+        let directory = temporary_directory("once");
+        fs::create_dir(directory.join("lib")).unwrap();
+        fs::write(directory.join("root.glsl"), "#pragma once\n#include \"lib/helper.glsl\"\nvoid main() {}\n").unwrap();
+        fs::write(directory.join("lib/helper.glsl"), "#include \"../root.glsl\"\n").unwrap();
+
+        // The root file is reached through a different path than from the helper
+        let result = Preprocessor::new().process_file(directory.join("lib/../root.glsl"));
+        fs::remove_dir_all(&directory).unwrap();
+
+        let source = result.expect("Could not preprocess file").source;
+        assert_eq!(source.matches("void main()").count(), 1);
+    }
+
+    #[test]
+    fn detects_include_cycles_through_root_file() {
+        let directory = temporary_directory("cycle");
+        fs::create_dir(directory.join("lib")).unwrap();
+        fs::write(directory.join("root.glsl"), "#include \"lib/helper.glsl\"\n").unwrap();
+        fs::write(directory.join("lib/helper.glsl"), "#include \"../root.glsl\"\n").unwrap();
+
+        let result = Preprocessor::new().process_file(directory.join("lib/../root.glsl"));
+        fs::remove_dir_all(&directory).unwrap();
+
+        match result {
+            Err(ShaderError::Preprocess { line, message, .. }) => {
+                assert_eq!((line, message.as_str()), (1, "Including '../root.glsl' would create an include cycle"));
+            }
+            result => panic!("Expected a preprocess error, got {:?}", result),
+        }
+    }
+}
@@ -0,0 +1,392 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use gl::types::{GLchar, GLenum, GLint, GLsizei, GLuint};
+
+use crate::gl_call;
+use crate::shader::ShaderType;
+use crate::util::string_to_c_string;
+
+/// The type of a uniform or attribute as declared in GLSL
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GlslType {
+    FLOAT,
+    VEC2,
+    VEC3,
+    VEC4,
+    DOUBLE,
+    DVEC2,
+    DVEC3,
+    DVEC4,
+    INT,
+    IVEC2,
+    IVEC3,
+    IVEC4,
+    UINT,
+    UVEC2,
+    UVEC3,
+    UVEC4,
+    BOOL,
+    BVEC2,
+    BVEC3,
+    BVEC4,
+    MAT2,
+    MAT3,
+    MAT4,
+    MAT2x3,
+    MAT2x4,
+    MAT3x2,
+    MAT3x4,
+    MAT4x2,
+    MAT4x3,
+    DMAT2,
+    DMAT3,
+    DMAT4,
+    SAMPLER_1D,
+    SAMPLER_2D,
+    SAMPLER_3D,
+    SAMPLER_CUBE,
+    SAMPLER_2D_SHADOW,
+    SAMPLER_2D_ARRAY,
+    ISAMPLER_2D,
+    USAMPLER_2D,
+    IMAGE_2D,
+    IIMAGE_2D,
+    UIMAGE_2D,
+    /// Any other type, as OpenGL enum
+    OTHER(GLenum),
+}
+
+/// An active uniform of a linked program
+#[derive(Clone, Debug)]
+pub struct ActiveUniform {
+    /// The name without the `[0]` suffix of arrays
+    pub name: String,
+    /// The location, or `None` for uniforms of uniform blocks
+    pub location: Option<GLint>,
+    pub r#type: GlslType,
+    /// The number of array elements, or 1 if the uniform is not an array
+    pub size: u32,
+}
+
+/// An active vertex attribute of a linked program
+#[derive(Clone, Debug)]
+pub struct ActiveAttribute {
+    pub name: String,
+    /// The location, or `None` for built-in attributes like `gl_VertexID`
+    pub location: Option<GLint>,
+    pub r#type: GlslType,
+    /// The number of array elements, or 1 if the attribute is not an array
+    pub size: u32,
+}
+
+/// An active uniform block of a linked program
+#[derive(Clone, Debug)]
+pub struct ActiveUniformBlock {
+    pub name: String,
+    pub index: GLuint,
+    /// The minimum size in bytes of a buffer bound to this block
+    pub data_size: usize,
+}
+
+/// The active uniforms and attributes of a linked program, as queried after linking.
+///
+/// Locations of names that are not in the reflection data, e.g. single elements of arrays like `lights[2]`,
+/// are queried once and cached as well.
+pub struct ProgramReflection {
+    program: GLuint,
+    stages: Vec<ShaderType>,
+    work_group_size: Option<[u32; 3]>,
+    uniforms: Vec<ActiveUniform>,
+    attributes: Vec<ActiveAttribute>,
+    uniform_blocks: Vec<ActiveUniformBlock>,
+    uniform_indices: HashMap<String, usize>,
+    uniform_locations: RefCell<HashMap<String, Option<GLint>>>,
+}
+
+impl ProgramReflection {
+    pub(crate) fn query(program: GLuint, stages: Vec<ShaderType>) -> Self {
+        let uniforms: Vec<ActiveUniform> = query_active(program, gl::ACTIVE_UNIFORMS, gl::ACTIVE_UNIFORM_MAX_LENGTH, gl::GetActiveUniform)
+            .into_iter()
+            .map(|(name, size, r#type)| ActiveUniform {
+                location: uniform_location(program, &name),
+                name,
+                r#type,
+                size,
+            })
+            .collect();
+
+        let attributes = query_active(program, gl::ACTIVE_ATTRIBUTES, gl::ACTIVE_ATTRIBUTE_MAX_LENGTH, gl::GetActiveAttrib)
+            .into_iter()
+            .map(|(name, size, r#type)| {
+                let location = gl_call!(gl::GetAttribLocation(program, string_to_c_string(&name).as_ptr()));
+                ActiveAttribute {
+                    location: if location == -1 { None } else { Some(location) },
+                    name,
+                    r#type,
+                    size,
+                }
+            })
+            .collect();
+
+        let uniform_blocks = query_uniform_blocks(program);
+
+        // Querying the work group size of programs without compute shader is an error
+        let work_group_size = stages.contains(&ShaderType::COMPUTE).then(|| {
+            let mut size: [GLint; 3] = [0; 3];
+            gl_call!(gl::GetProgramiv(program, gl::COMPUTE_WORK_GROUP_SIZE, size.as_mut_ptr()));
+            size.map(|size| size as u32)
+        });
+
+        let uniform_indices = uniforms.iter()
+            .enumerate()
+            .map(|(index, uniform)| (uniform.name.clone(), index))
+            .collect();
+
+        Self {
+            program,
+            stages,
+            work_group_size,
+            uniforms,
+            attributes,
+            uniform_blocks,
+            uniform_indices,
+            uniform_locations: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// The OpenGL id of the program this data was queried from
+    pub fn program(&self) -> GLuint {
+        self.program
+    }
+
+    /// The stages of the shaders the program was linked from
+    pub fn stages(&self) -> &[ShaderType] {
+        &self.stages
+    }
+
+    /// The local work group size declared by the compute shader, or `None` if the program has no compute shader
+    pub fn work_group_size(&self) -> Option<[u32; 3]> {
+        self.work_group_size
+    }
+
+    pub fn uniforms(&self) -> &[ActiveUniform] {
+        &self.uniforms
+    }
+
+    pub fn attributes(&self) -> &[ActiveAttribute] {
+        &self.attributes
+    }
+
+    pub fn uniform_blocks(&self) -> &[ActiveUniformBlock] {
+        &self.uniform_blocks
+    }
+
+    pub fn uniform_block(&self, name: &str) -> Option<&ActiveUniformBlock> {
+        self.uniform_blocks.iter().find(|block| block.name == name)
+    }
+
+    /// The active uniform with the given name, arrays are named without the `[0]` suffix
+    pub fn uniform(&self, name: &str) -> Option<&ActiveUniform> {
+        self.uniform_indices.get(strip_first_element(name)).map(|index| &self.uniforms[*index])
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&ActiveAttribute> {
+        self.attributes.iter().find(|attribute| attribute.name == name)
+    }
+
+    /// The active uniform with the given name and the index of the named element for names of single array elements,
+    /// e.g. `lights[2]`
+    pub(crate) fn uniform_element(&self, name: &str) -> Option<(&ActiveUniform, u32)> {
+        if let Some(uniform) = self.uniform(name) {
+            return Some((uniform, 0));
+        }
+
+        let (array_name, index) = name.strip_suffix(']')?.rsplit_once('[')?;
+        Some((self.uniform(array_name)?, index.parse().ok()?))
+    }
+
+    /// The location of the uniform with the given name, or `None` if it is not used by the program
+    pub fn uniform_location(&self, name: &str) -> Option<GLint> {
+        if let Some(uniform) = self.uniform(name) {
+            return uniform.location;
+        }
+
+        *self.uniform_locations.borrow_mut()
+            .entry(name.to_string())
+            .or_insert_with(|| uniform_location(self.program, name))
+    }
+}
+
+impl GlslType {
+    pub fn from_gl(gl_enum: GLenum) -> Self {
+        match gl_enum {
+            gl::FLOAT => GlslType::FLOAT,
+            gl::FLOAT_VEC2 => GlslType::VEC2,
+            gl::FLOAT_VEC3 => GlslType::VEC3,
+            gl::FLOAT_VEC4 => GlslType::VEC4,
+            gl::DOUBLE => GlslType::DOUBLE,
+            gl::DOUBLE_VEC2 => GlslType::DVEC2,
+            gl::DOUBLE_VEC3 => GlslType::DVEC3,
+            gl::DOUBLE_VEC4 => GlslType::DVEC4,
+            gl::INT => GlslType::INT,
+            gl::INT_VEC2 => GlslType::IVEC2,
+            gl::INT_VEC3 => GlslType::IVEC3,
+            gl::INT_VEC4 => GlslType::IVEC4,
+            gl::UNSIGNED_INT => GlslType::UINT,
+            gl::UNSIGNED_INT_VEC2 => GlslType::UVEC2,
+            gl::UNSIGNED_INT_VEC3 => GlslType::UVEC3,
+            gl::UNSIGNED_INT_VEC4 => GlslType::UVEC4,
+            gl::BOOL => GlslType::BOOL,
+            gl::BOOL_VEC2 => GlslType::BVEC2,
+            gl::BOOL_VEC3 => GlslType::BVEC3,
+            gl::BOOL_VEC4 => GlslType::BVEC4,
+            gl::FLOAT_MAT2 => GlslType::MAT2,
+            gl::FLOAT_MAT3 => GlslType::MAT3,
+            gl::FLOAT_MAT4 => GlslType::MAT4,
+            gl::FLOAT_MAT2x3 => GlslType::MAT2x3,
+            gl::FLOAT_MAT2x4 => GlslType::MAT2x4,
+            gl::FLOAT_MAT3x2 => GlslType::MAT3x2,
+            gl::FLOAT_MAT3x4 => GlslType::MAT3x4,
+            gl::FLOAT_MAT4x2 => GlslType::MAT4x2,
+            gl::FLOAT_MAT4x3 => GlslType::MAT4x3,
+            gl::DOUBLE_MAT2 => GlslType::DMAT2,
+            gl::DOUBLE_MAT3 => GlslType::DMAT3,
+            gl::DOUBLE_MAT4 => GlslType::DMAT4,
+            gl::SAMPLER_1D => GlslType::SAMPLER_1D,
+            gl::SAMPLER_2D => GlslType::SAMPLER_2D,
+            gl::SAMPLER_3D => GlslType::SAMPLER_3D,
+            gl::SAMPLER_CUBE => GlslType::SAMPLER_CUBE,
+            gl::SAMPLER_2D_SHADOW => GlslType::SAMPLER_2D_SHADOW,
+            gl::SAMPLER_2D_ARRAY => GlslType::SAMPLER_2D_ARRAY,
+            gl::INT_SAMPLER_2D => GlslType::ISAMPLER_2D,
+            gl::UNSIGNED_INT_SAMPLER_2D => GlslType::USAMPLER_2D,
+            gl::IMAGE_2D => GlslType::IMAGE_2D,
+            gl::INT_IMAGE_2D => GlslType::IIMAGE_2D,
+            gl::UNSIGNED_INT_IMAGE_2D => GlslType::UIMAGE_2D,
+            other => GlslType::OTHER(other),
+        }
+    }
+
+    /// Whether uniforms of this type are set with the texture or image unit they read from
+    pub fn is_opaque(&self) -> bool {
+        matches!(self,
+            GlslType::SAMPLER_1D | GlslType::SAMPLER_2D | GlslType::SAMPLER_3D | GlslType::SAMPLER_CUBE |
+            GlslType::SAMPLER_2D_SHADOW | GlslType::SAMPLER_2D_ARRAY | GlslType::ISAMPLER_2D | GlslType::USAMPLER_2D |
+            GlslType::IMAGE_2D | GlslType::IIMAGE_2D | GlslType::UIMAGE_2D)
+    }
+}
+
+impl Display for GlslType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            GlslType::FLOAT => "float",
+            GlslType::VEC2 => "vec2",
+            GlslType::VEC3 => "vec3",
+            GlslType::VEC4 => "vec4",
+            GlslType::DOUBLE => "double",
+            GlslType::DVEC2 => "dvec2",
+            GlslType::DVEC3 => "dvec3",
+            GlslType::DVEC4 => "dvec4",
+            GlslType::INT => "int",
+            GlslType::IVEC2 => "ivec2",
+            GlslType::IVEC3 => "ivec3",
+            GlslType::IVEC4 => "ivec4",
+            GlslType::UINT => "uint",
+            GlslType::UVEC2 => "uvec2",
+            GlslType::UVEC3 => "uvec3",
+            GlslType::UVEC4 => "uvec4",
+            GlslType::BOOL => "bool",
+            GlslType::BVEC2 => "bvec2",
+            GlslType::BVEC3 => "bvec3",
+            GlslType::BVEC4 => "bvec4",
+            GlslType::MAT2 => "mat2",
+            GlslType::MAT3 => "mat3",
+            GlslType::MAT4 => "mat4",
+            GlslType::MAT2x3 => "mat2x3",
+            GlslType::MAT2x4 => "mat2x4",
+            GlslType::MAT3x2 => "mat3x2",
+            GlslType::MAT3x4 => "mat3x4",
+            GlslType::MAT4x2 => "mat4x2",
+            GlslType::MAT4x3 => "mat4x3",
+            GlslType::DMAT2 => "dmat2",
+            GlslType::DMAT3 => "dmat3",
+            GlslType::DMAT4 => "dmat4",
+            GlslType::SAMPLER_1D => "sampler1D",
+            GlslType::SAMPLER_2D => "sampler2D",
+            GlslType::SAMPLER_3D => "sampler3D",
+            GlslType::SAMPLER_CUBE => "samplerCube",
+            GlslType::SAMPLER_2D_SHADOW => "sampler2DShadow",
+            GlslType::SAMPLER_2D_ARRAY => "sampler2DArray",
+            GlslType::ISAMPLER_2D => "isampler2D",
+            GlslType::USAMPLER_2D => "usampler2D",
+            GlslType::IMAGE_2D => "image2D",
+            GlslType::IIMAGE_2D => "iimage2D",
+            GlslType::UIMAGE_2D => "uimage2D",
+            GlslType::OTHER(gl_enum) => return write!(f, "type 0x{:X}", gl_enum),
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Queries the name, array size and type of all active uniforms or attributes
+fn query_active(program: GLuint,
+                count_parameter: GLenum,
+                max_length_parameter: GLenum,
+                get_active: unsafe fn(GLuint, GLuint, GLsizei, *mut GLsizei, *mut GLint, *mut GLenum, *mut GLchar)) -> Vec<(String, u32, GlslType)> {
+    let (mut count, mut max_length): (GLint, GLint) = (0, 0);
+    gl_call!(gl::GetProgramiv(program, count_parameter, &mut count));
+    gl_call!(gl::GetProgramiv(program, max_length_parameter, &mut max_length));
+
+    (0..count as GLuint)
+        .map(|index| {
+            let mut name: Vec<u8> = vec![0; max_length.max(1) as usize];
+            let (mut length, mut size, mut r#type): (GLsizei, GLint, GLenum) = (0, 0, 0);
+            gl_call!(get_active(program, index, name.len() as GLsizei, &mut length, &mut size, &mut r#type, name.as_mut_ptr() as *mut GLchar));
+            name.truncate(length as usize);
+
+            let name = String::from_utf8_lossy(&name);
+            (strip_first_element(&name).to_string(), size as u32, GlslType::from_gl(r#type))
+        })
+        .collect()
+}
+
+fn query_uniform_blocks(program: GLuint) -> Vec<ActiveUniformBlock> {
+    let (mut count, mut max_length): (GLint, GLint) = (0, 0);
+    gl_call!(gl::GetProgramiv(program, gl::ACTIVE_UNIFORM_BLOCKS, &mut count));
+    gl_call!(gl::GetProgramiv(program, gl::ACTIVE_UNIFORM_BLOCK_MAX_NAME_LENGTH, &mut max_length));
+
+    (0..count as GLuint)
+        .map(|index| {
+            let mut name: Vec<u8> = vec![0; max_length.max(1) as usize];
+            let mut length: GLsizei = 0;
+            gl_call!(gl::GetActiveUniformBlockName(program, index, name.len() as GLsizei, &mut length, name.as_mut_ptr() as *mut GLchar));
+            name.truncate(length as usize);
+
+            let mut data_size: GLint = 0;
+            gl_call!(gl::GetActiveUniformBlockiv(program, index, gl::UNIFORM_BLOCK_DATA_SIZE, &mut data_size));
+
+            ActiveUniformBlock {
+                name: String::from_utf8_lossy(&name).into_owned(),
+                index,
+                data_size: data_size as usize,
+            }
+        })
+        .collect()
+}
+
+/// Arrays are reported as their first element, e.g. `lights[0]`
+fn strip_first_element(name: &str) -> &str {
+    name.strip_suffix("[0]").unwrap_or(name)
+}
+
+/// Queries the location of a uniform. A location of -1 is returned when the uniform is not used in the shaders.
+fn uniform_location(program: GLuint, name: &str) -> Option<GLint> {
+    let location = gl_call!(gl::GetUniformLocation(program, string_to_c_string(name).as_ptr()));
+    if location == -1 {
+        return None;
+    }
+    Some(location)
+}
@@ -0,0 +1,289 @@
+use std::borrow::Cow;
+
+use gl::types::{GLboolean, GLint, GLsizei};
+use glam::{BVec2, BVec3, BVec4, DMat2, DMat3, DMat4, DVec2, DVec3, DVec4, IVec2, IVec3, IVec4, Mat2, Mat3, Mat4, UVec2, UVec3, UVec4, Vec2, Vec3, Vec4};
+
+use crate::data::std140::align_to;
+use crate::gl_call;
+use crate::shader::{GlslType, ProgramReflection, UniformError};
+
+/// The components of all elements of a uniform value, in the scalar type they are uploaded as
+#[derive(Clone, Debug)]
+pub enum UniformComponents<'a> {
+    Float(Cow<'a, [f32]>),
+    Double(Cow<'a, [f64]>),
+    Int(Cow<'a, [i32]>),
+    UInt(Cow<'a, [u32]>),
+}
+
+/// A value that can be assigned to a uniform with `ShaderProgram::set_uniform`.
+///
+/// Arrays, slices and vectors of `UniformElement`s are assigned to uniform arrays, starting at the given element.
+pub trait Uniform {
+    /// The GLSL type of a single element
+    const TYPE: GlslType;
+
+    /// The number of array elements, 1 for single values
+    fn count(&self) -> usize;
+
+    fn components(&self) -> UniformComponents<'_>;
+}
+
+/// A uniform type that can be used as element of uniform arrays
+pub trait UniformElement: Uniform + Sized {
+    fn array_components(values: &[Self]) -> UniformComponents<'_>;
+}
+
+/// A uniform value that is kept to be set again after the program has been reloaded
+#[derive(Clone)]
+pub(crate) struct UniformValue {
+    r#type: GlslType,
+    count: usize,
+    components: UniformComponents<'static>,
+}
+
+impl UniformValue {
+    pub(crate) fn new<U: Uniform>(value: &U) -> Self {
+        Self {
+            r#type: U::TYPE,
+            count: value.count(),
+            components: value.components().into_owned(),
+        }
+    }
+
+    /// Sets this value for the uniform with the given name of the currently bound program
+    pub(crate) fn upload(&self, reflection: &ProgramReflection, name: &str) -> Result<(), UniformError> {
+        upload(reflection, name, self.r#type, self.count, &self.components)
+    }
+}
+
+impl UniformComponents<'_> {
+    pub fn into_owned(self) -> UniformComponents<'static> {
+        match self {
+            UniformComponents::Float(data) => UniformComponents::Float(Cow::Owned(data.into_owned())),
+            UniformComponents::Double(data) => UniformComponents::Double(Cow::Owned(data.into_owned())),
+            UniformComponents::Int(data) => UniformComponents::Int(Cow::Owned(data.into_owned())),
+            UniformComponents::UInt(data) => UniformComponents::UInt(Cow::Owned(data.into_owned())),
+        }
+    }
+}
+
+/// Checks the value against the reflected type and array size of the uniform with the given name and sets it
+/// for the currently bound program. Uniforms that are not used by the program are ignored.
+pub(crate) fn upload(reflection: &ProgramReflection,
+                     name: &str,
+                     r#type: GlslType,
+                     count: usize,
+                     components: &UniformComponents) -> Result<(), UniformError> {
+    let Some(location) = reflection.uniform_location(name) else {
+        return Ok(());
+    };
+
+    if let Some((uniform, index)) = reflection.uniform_element(name) {
+        if !is_assignable(uniform.r#type, r#type) {
+            return Err(UniformError::TypeMismatch { name: name.to_string(), expected: uniform.r#type, actual: r#type });
+        }
+        if index as usize + count > uniform.size as usize {
+            return Err(UniformError::TooManyElements { name: name.to_string(), size: uniform.size - index, count });
+        }
+    }
+
+    let count = count as GLsizei;
+    match components {
+        UniformComponents::Float(data) => match r#type {
+            GlslType::MAT2 => upload_matrix(location, count, data, gl::UniformMatrix2fv),
+            GlslType::MAT3 => upload_matrix(location, count, data, gl::UniformMatrix3fv),
+            GlslType::MAT4 => upload_matrix(location, count, data, gl::UniformMatrix4fv),
+            _ => upload_vector(location, count, data, r#type, [gl::Uniform1fv, gl::Uniform2fv, gl::Uniform3fv, gl::Uniform4fv]),
+        },
+        UniformComponents::Double(data) => match r#type {
+            GlslType::DMAT2 => upload_matrix(location, count, data, gl::UniformMatrix2dv),
+            GlslType::DMAT3 => upload_matrix(location, count, data, gl::UniformMatrix3dv),
+            GlslType::DMAT4 => upload_matrix(location, count, data, gl::UniformMatrix4dv),
+            _ => upload_vector(location, count, data, r#type, [gl::Uniform1dv, gl::Uniform2dv, gl::Uniform3dv, gl::Uniform4dv]),
+        },
+        UniformComponents::Int(data) => upload_vector(location, count, data, r#type, [gl::Uniform1iv, gl::Uniform2iv, gl::Uniform3iv, gl::Uniform4iv]),
+        UniformComponents::UInt(data) => upload_vector(location, count, data, r#type, [gl::Uniform1uiv, gl::Uniform2uiv, gl::Uniform3uiv, gl::Uniform4uiv]),
+    }
+
+    Ok(())
+}
+
+/// Checks the std140 size of a buffer's value against the size of the uniform block with the given name and assigns
+/// the binding point to the block. Blocks that are not used by the program are ignored.
+pub(crate) fn bind_block(reflection: &ProgramReflection, name: &str, binding: u32, size: usize) -> Result<(), UniformError> {
+    let Some(block) = reflection.uniform_block(name) else {
+        return Ok(());
+    };
+
+    // Drivers may report the size without the padding at the end of the block, and std140 sizes of scalars and
+    // vectors are not padded at all
+    if align_to(block.data_size, 16) != align_to(size, 16) {
+        return Err(UniformError::BlockSizeMismatch { name: name.to_string(), expected: block.data_size, actual: size });
+    }
+
+    gl_call!(gl::UniformBlockBinding(reflection.program(), block.index, binding));
+    Ok(())
+}
+
+fn upload_vector<T>(location: GLint, count: GLsizei, data: &[T], r#type: GlslType, gl_functions: [unsafe fn(GLint, GLsizei, *const T); 4]) {
+    gl_call!(gl_functions[vector_size(r#type) - 1](location, count, data.as_ptr()));
+}
+
+fn upload_matrix<T>(location: GLint, count: GLsizei, data: &[T], gl_function: unsafe fn(GLint, GLsizei, GLboolean, *const T)) {
+    // glam matrices are stored in column major order, as OpenGL expects them
+    gl_call!(gl_function(location, count, gl::FALSE, data.as_ptr()));
+}
+
+fn vector_size(r#type: GlslType) -> usize {
+    match r#type {
+        GlslType::VEC2 | GlslType::DVEC2 | GlslType::IVEC2 | GlslType::UVEC2 | GlslType::BVEC2 => 2,
+        GlslType::VEC3 | GlslType::DVEC3 | GlslType::IVEC3 | GlslType::UVEC3 | GlslType::BVEC3 => 3,
+        GlslType::VEC4 | GlslType::DVEC4 | GlslType::IVEC4 | GlslType::UVEC4 | GlslType::BVEC4 => 4,
+        _ => 1,
+    }
+}
+
+/// Whether a value of the given type can be assigned to a uniform of the given type.
+///
+/// Besides values of the same type, OpenGL allows setting samplers and images with the int of their unit,
+/// and booleans with ints, unsigned ints or floats of the same size.
+fn is_assignable(uniform: GlslType, value: GlslType) -> bool {
+    uniform == value
+        || (uniform.is_opaque() && value == GlslType::INT)
+        || matches!((uniform, value),
+            (GlslType::BOOL, GlslType::BOOL | GlslType::INT | GlslType::UINT | GlslType::FLOAT) |
+            (GlslType::BVEC2, GlslType::BVEC2 | GlslType::IVEC2 | GlslType::UVEC2 | GlslType::VEC2) |
+            (GlslType::BVEC3, GlslType::BVEC3 | GlslType::IVEC3 | GlslType::UVEC3 | GlslType::VEC3) |
+            (GlslType::BVEC4, GlslType::BVEC4 | GlslType::IVEC4 | GlslType::UVEC4 | GlslType::VEC4))
+}
+
+/// Implements `Uniform` for types that consist of a fixed number of scalars and can be uploaded without copying
+macro_rules! impl_pod_uniform {
+    ($($rust_type:ty => $glsl_type:ident, $components:ident;)*) => {$(
+        impl Uniform for $rust_type {
+            const TYPE: GlslType = GlslType::$glsl_type;
+
+            fn count(&self) -> usize {
+                1
+            }
+
+            fn components(&self) -> UniformComponents<'_> {
+                Self::array_components(std::slice::from_ref(self))
+            }
+        }
+
+        impl UniformElement for $rust_type {
+            fn array_components(values: &[Self]) -> UniformComponents<'_> {
+                UniformComponents::$components(Cow::Borrowed(bytemuck::cast_slice(values)))
+            }
+        }
+    )*};
+}
+
+impl_pod_uniform! {
+    f32 => FLOAT, Float;
+    Vec2 => VEC2, Float;
+    Vec3 => VEC3, Float;
+    Vec4 => VEC4, Float;
+    Mat2 => MAT2, Float;
+    Mat3 => MAT3, Float;
+    Mat4 => MAT4, Float;
+    f64 => DOUBLE, Double;
+    DVec2 => DVEC2, Double;
+    DVec3 => DVEC3, Double;
+    DVec4 => DVEC4, Double;
+    DMat2 => DMAT2, Double;
+    DMat3 => DMAT3, Double;
+    DMat4 => DMAT4, Double;
+    i32 => INT, Int;
+    IVec2 => IVEC2, Int;
+    IVec3 => IVEC3, Int;
+    IVec4 => IVEC4, Int;
+    u32 => UINT, UInt;
+    UVec2 => UVEC2, UInt;
+    UVec3 => UVEC3, UInt;
+    UVec4 => UVEC4, UInt;
+}
+
+/// Implements `Uniform` for booleans, which are converted to unsigned ints
+macro_rules! impl_bool_uniform {
+    ($($rust_type:ty => $glsl_type:ident, $to_array:expr;)*) => {$(
+        impl Uniform for $rust_type {
+            const TYPE: GlslType = GlslType::$glsl_type;
+
+            fn count(&self) -> usize {
+                1
+            }
+
+            fn components(&self) -> UniformComponents<'_> {
+                Self::array_components(std::slice::from_ref(self))
+            }
+        }
+
+        impl UniformElement for $rust_type {
+            fn array_components(values: &[Self]) -> UniformComponents<'_> {
+                UniformComponents::UInt(Cow::Owned(values.iter().flat_map(|value| ($to_array)(*value)).collect()))
+            }
+        }
+    )*};
+}
+
+impl_bool_uniform! {
+    bool => BOOL, bool_to_array;
+    BVec2 => BVEC2, <[u32; 2]>::from;
+    BVec3 => BVEC3, <[u32; 3]>::from;
+    BVec4 => BVEC4, <[u32; 4]>::from;
+}
+
+fn bool_to_array(value: bool) -> [u32; 1] {
+    [u32::from(value)]
+}
+
+impl<T: UniformElement> Uniform for [T] {
+    const TYPE: GlslType = T::TYPE;
+
+    fn count(&self) -> usize {
+        self.len()
+    }
+
+    fn components(&self) -> UniformComponents<'_> {
+        T::array_components(self)
+    }
+}
+
+impl<T: UniformElement, const N: usize> Uniform for [T; N] {
+    const TYPE: GlslType = T::TYPE;
+
+    fn count(&self) -> usize {
+        N
+    }
+
+    fn components(&self) -> UniformComponents<'_> {
+        T::array_components(self)
+    }
+}
+
+impl<T: UniformElement> Uniform for Vec<T> {
+    const TYPE: GlslType = T::TYPE;
+
+    fn count(&self) -> usize {
+        self.len()
+    }
+
+    fn components(&self) -> UniformComponents<'_> {
+        T::array_components(self)
+    }
+}
+
+impl<T: Uniform + ?Sized> Uniform for &T {
+    const TYPE: GlslType = T::TYPE;
+
+    fn count(&self) -> usize {
+        (**self).count()
+    }
+
+    fn components(&self) -> UniformComponents<'_> {
+        (**self).components()
+    }
+}
@@ -0,0 +1,225 @@
+//! Golden image tests, available with the `testing` feature.
+//!
+//! A golden image test renders a scene in a headless window, reads back the result and compares it against a
+//! reference PNG file. If the environment variable `BOWL_UPDATE_GOLDEN` is set, the reference is (re)written instead.
+//!
+//! GLFW must only be used from the main thread, so golden image tests should run in a test target with `harness = false`.
+
+use std::env;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::data::image::{ImageError, RgbaImage};
+use crate::window::{WindowBuilder, WindowError, WindowHandle};
+
+const UPDATE_REFERENCES_VARIABLE: &str = "BOWL_UPDATE_GOLDEN";
+
+pub struct GoldenTest {
+    name: String,
+    width: u32,
+    height: u32,
+    frames: u32,
+    tolerance: u8,
+    max_mismatched_pixels: usize,
+    reference_dir: PathBuf,
+    output_dir: PathBuf,
+}
+
+/// The result of a golden image test that did not fail
+#[derive(Debug, PartialEq, Eq)]
+pub enum GoldenTestOutcome {
+    /// The rendered image matches the reference image
+    Matched,
+    /// `BOWL_UPDATE_GOLDEN` is set, so the rendered image was written as reference without comparing anything
+    ReferenceWritten {
+        path: PathBuf,
+    },
+}
+
+#[derive(Debug)]
+pub enum GoldenTestError {
+    WindowCreation(WindowError),
+    MissingReference {
+        path: PathBuf,
+    },
+    SizeMismatch {
+        expected: (u32, u32),
+        actual: (u32, u32),
+    },
+    /// More pixels than allowed differ by more than the tolerance in at least one channel
+    Mismatch {
+        mismatched_pixels: usize,
+        max_channel_difference: u8,
+        actual_path: PathBuf,
+        diff_path: PathBuf,
+    },
+    Image(ImageError),
+    Io(std::io::Error),
+}
+
+impl GoldenTest {
+    /// Creates a golden image test, whose reference image is `<reference_dir>/<name>.png`
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            width: 256,
+            height: 256,
+            frames: 1,
+            tolerance: 0,
+            max_mismatched_pixels: 0,
+            reference_dir: PathBuf::from("tests/golden"),
+            output_dir: env::temp_dir().join("bowl-golden"),
+        }
+    }
+
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        return self;
+    }
+    /// The number of frames rendered before the result is read back
+    pub fn frames(mut self, frames: u32) -> Self {
+        self.frames = frames;
+        return self;
+    }
+    /// The maximum difference per color channel for two pixels to still be considered equal
+    pub fn tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = tolerance;
+        return self;
+    }
+    pub fn max_mismatched_pixels(mut self, max_mismatched_pixels: usize) -> Self {
+        self.max_mismatched_pixels = max_mismatched_pixels;
+        return self;
+    }
+    pub fn reference_dir<P: Into<PathBuf>>(mut self, reference_dir: P) -> Self {
+        self.reference_dir = reference_dir.into();
+        return self;
+    }
+    /// The directory the actual and diff images of failed tests are written to
+    pub fn output_dir<P: Into<PathBuf>>(mut self, output_dir: P) -> Self {
+        self.output_dir = output_dir.into();
+        return self;
+    }
+
+    /// Renders the scene and compares the last frame against the reference image.
+    ///
+    /// `setup` is called once the OpenGL context exists, to create all resources of the scene.
+    /// It returns the closure that renders a single frame.
+    pub fn run<S, F>(self, setup: S) -> Result<GoldenTestOutcome, GoldenTestError>
+        where S: FnOnce() -> F,
+              F: FnMut(&mut WindowHandle) {
+        let mut window = WindowBuilder::new()
+            .size(self.width, self.height)
+            .headless(true)
+            .create()
+            .map_err(GoldenTestError::WindowCreation)?;
+
+        let actual = {
+            let mut render = setup();
+            for _ in 0..self.frames {
+                window.frame(&mut render);
+            }
+            window.capture()
+        };
+
+        self.compare(&actual)
+    }
+
+    /// Like `run`, but panics with a readable message if the test fails
+    pub fn assert_matches_reference<S, F>(self, setup: S)
+        where S: FnOnce() -> F,
+              F: FnMut(&mut WindowHandle) {
+        let name = self.name.clone();
+        match self.run(setup) {
+            Ok(GoldenTestOutcome::Matched) => {}
+            Ok(GoldenTestOutcome::ReferenceWritten { path }) => {
+                eprintln!("[Bowl] Wrote reference image {} of golden image test '{}', nothing was compared", path.display(), name);
+            }
+            Err(error) => panic!("Golden image test '{}' failed: {}", name, error),
+        }
+    }
+
+    fn compare(&self, actual: &RgbaImage) -> Result<GoldenTestOutcome, GoldenTestError> {
+        let reference_path = self.reference_dir.join(format!("{}.png", self.name));
+
+        if env::var_os(UPDATE_REFERENCES_VARIABLE).is_some() {
+            fs::create_dir_all(&self.reference_dir).map_err(GoldenTestError::Io)?;
+            actual.save_png(&reference_path).map_err(GoldenTestError::Image)?;
+            return Ok(GoldenTestOutcome::ReferenceWritten { path: reference_path });
+        }
+
+        if !reference_path.exists() {
+            return Err(GoldenTestError::MissingReference { path: reference_path });
+        }
+        let reference = RgbaImage::load_png(&reference_path).map_err(GoldenTestError::Image)?;
+
+        if (reference.width, reference.height) != (actual.width, actual.height) {
+            return Err(GoldenTestError::SizeMismatch {
+                expected: (reference.width, reference.height),
+                actual: (actual.width, actual.height),
+            });
+        }
+
+        let mut diff = RgbaImage { width: actual.width, height: actual.height, data: Vec::with_capacity(actual.data.len()) };
+        let mut mismatched_pixels = 0;
+        let mut max_channel_difference = 0;
+
+        for (expected_pixel, actual_pixel) in reference.data.chunks_exact(4).zip(actual.data.chunks_exact(4)) {
+            let difference = expected_pixel.iter().zip(actual_pixel)
+                .map(|(expected, actual)| expected.abs_diff(*actual))
+                .max()
+                .unwrap_or(0);
+            max_channel_difference = max_channel_difference.max(difference);
+
+            if difference > self.tolerance {
+                mismatched_pixels += 1;
+                diff.data.extend_from_slice(&[255, 0, 0, 255]);
+            } else {
+                // Matching pixels are shown dimmed in grayscale to give some context
+                let luminance = ((expected_pixel[0] as u32 + expected_pixel[1] as u32 + expected_pixel[2] as u32) / 12) as u8;
+                diff.data.extend_from_slice(&[luminance, luminance, luminance, 255]);
+            }
+        }
+
+        if mismatched_pixels <= self.max_mismatched_pixels {
+            return Ok(GoldenTestOutcome::Matched);
+        }
+
+        fs::create_dir_all(&self.output_dir).map_err(GoldenTestError::Io)?;
+        let actual_path = self.output_dir.join(format!("{}.actual.png", self.name));
+        let diff_path = self.output_dir.join(format!("{}.diff.png", self.name));
+        actual.save_png(&actual_path).map_err(GoldenTestError::Image)?;
+        diff.save_png(&diff_path).map_err(GoldenTestError::Image)?;
+
+        Err(GoldenTestError::Mismatch {
+            mismatched_pixels,
+            max_channel_difference,
+            actual_path,
+            diff_path,
+        })
+    }
+}
+
+impl Display for GoldenTestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GoldenTestError::WindowCreation(error) => write!(f, "{}", error),
+            GoldenTestError::MissingReference { path } => {
+                write!(f, "[Bowl] Reference image {} does not exist, set {} to create it", path.display(), UPDATE_REFERENCES_VARIABLE)
+            }
+            GoldenTestError::SizeMismatch { expected, actual } => {
+                write!(f, "[Bowl] Expected an image of size {}x{}, but got {}x{}", expected.0, expected.1, actual.0, actual.1)
+            }
+            GoldenTestError::Mismatch { mismatched_pixels, max_channel_difference, actual_path, diff_path } => {
+                write!(f, "[Bowl] {} pixels differ from the reference image by up to {} per channel, see {} and {}",
+                       mismatched_pixels, max_channel_difference, actual_path.display(), diff_path.display())
+            }
+            GoldenTestError::Image(error) => write!(f, "{}", error),
+            GoldenTestError::Io(error) => write!(f, "[Bowl] {}", error),
+        }
+    }
+}
+
+impl Error for GoldenTestError {}
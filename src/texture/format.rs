@@ -0,0 +1,143 @@
+use gl::types::GLenum;
+
+/// The format a texture is stored in on the GPU.
+///
+/// The pixel data passed when creating a texture must be tightly packed in the same format, e.g. two bytes
+/// per channel in little endian half floats for `RGBA16F`. Integer formats must be sampled with `Filter::NEAREST`
+/// and without mipmaps, using the `isampler*` and `usampler*` sampler types in GLSL.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextureFormat {
+    R8,
+    RG8,
+    RGB8,
+    RGBA8,
+    SRGB8,
+    SRGB8_ALPHA8,
+    R16F,
+    RG16F,
+    RGB16F,
+    RGBA16F,
+    R32F,
+    RG32F,
+    RGB32F,
+    RGBA32F,
+    R8I,
+    R8UI,
+    R16I,
+    R16UI,
+    R32I,
+    R32UI,
+    RG32I,
+    RG32UI,
+    RGBA8I,
+    RGBA8UI,
+    RGBA32I,
+    RGBA32UI,
+    DEPTH_COMPONENT16,
+    DEPTH_COMPONENT24,
+    DEPTH_COMPONENT32F,
+    DEPTH24_STENCIL8,
+    DEPTH32F_STENCIL8,
+}
+
+impl TextureFormat {
+    pub fn bytes_per_pixel(self) -> usize {
+        let (_, format, r#type) = self.to_gl_enums();
+        let channels = match format {
+            gl::RED | gl::RED_INTEGER | gl::DEPTH_COMPONENT | gl::DEPTH_STENCIL => 1,
+            gl::RG | gl::RG_INTEGER => 2,
+            gl::RGB | gl::RGB_INTEGER => 3,
+            _ => 4,
+        };
+        let bytes_per_channel = match r#type {
+            gl::UNSIGNED_BYTE | gl::BYTE => 1,
+            gl::HALF_FLOAT | gl::UNSIGNED_SHORT | gl::SHORT => 2,
+            gl::FLOAT_32_UNSIGNED_INT_24_8_REV => 8,
+            _ => 4,
+        };
+        channels * bytes_per_channel
+    }
+
+    /// Whether the texture stores integers that are not normalized when sampled
+    pub fn is_integer(self) -> bool {
+        matches!(self.to_gl_enums().1, gl::RED_INTEGER | gl::RG_INTEGER | gl::RGB_INTEGER | gl::RGBA_INTEGER)
+    }
+
+    pub fn is_depth(self) -> bool {
+        matches!(self.to_gl_enums().1, gl::DEPTH_COMPONENT | gl::DEPTH_STENCIL)
+    }
+
+    pub fn has_stencil(self) -> bool {
+        self.to_gl_enums().1 == gl::DEPTH_STENCIL
+    }
+
+    /// Returns the internal format, the pixel format and the pixel type used to upload data of this format
+    pub(crate) fn to_gl_enums(self) -> (GLenum, GLenum, GLenum) {
+        match self {
+            TextureFormat::R8 => (gl::R8, gl::RED, gl::UNSIGNED_BYTE),
+            TextureFormat::RG8 => (gl::RG8, gl::RG, gl::UNSIGNED_BYTE),
+            TextureFormat::RGB8 => (gl::RGB8, gl::RGB, gl::UNSIGNED_BYTE),
+            TextureFormat::RGBA8 => (gl::RGBA8, gl::RGBA, gl::UNSIGNED_BYTE),
+            TextureFormat::SRGB8 => (gl::SRGB8, gl::RGB, gl::UNSIGNED_BYTE),
+            TextureFormat::SRGB8_ALPHA8 => (gl::SRGB8_ALPHA8, gl::RGBA, gl::UNSIGNED_BYTE),
+            TextureFormat::R16F => (gl::R16F, gl::RED, gl::HALF_FLOAT),
+            TextureFormat::RG16F => (gl::RG16F, gl::RG, gl::HALF_FLOAT),
+            TextureFormat::RGB16F => (gl::RGB16F, gl::RGB, gl::HALF_FLOAT),
+            TextureFormat::RGBA16F => (gl::RGBA16F, gl::RGBA, gl::HALF_FLOAT),
+            TextureFormat::R32F => (gl::R32F, gl::RED, gl::FLOAT),
+            TextureFormat::RG32F => (gl::RG32F, gl::RG, gl::FLOAT),
+            TextureFormat::RGB32F => (gl::RGB32F, gl::RGB, gl::FLOAT),
+            TextureFormat::RGBA32F => (gl::RGBA32F, gl::RGBA, gl::FLOAT),
+            TextureFormat::R8I => (gl::R8I, gl::RED_INTEGER, gl::BYTE),
+            TextureFormat::R8UI => (gl::R8UI, gl::RED_INTEGER, gl::UNSIGNED_BYTE),
+            TextureFormat::R16I => (gl::R16I, gl::RED_INTEGER, gl::SHORT),
+            TextureFormat::R16UI => (gl::R16UI, gl::RED_INTEGER, gl::UNSIGNED_SHORT),
+            TextureFormat::R32I => (gl::R32I, gl::RED_INTEGER, gl::INT),
+            TextureFormat::R32UI => (gl::R32UI, gl::RED_INTEGER, gl::UNSIGNED_INT),
+            TextureFormat::RG32I => (gl::RG32I, gl::RG_INTEGER, gl::INT),
+            TextureFormat::RG32UI => (gl::RG32UI, gl::RG_INTEGER, gl::UNSIGNED_INT),
+            TextureFormat::RGBA8I => (gl::RGBA8I, gl::RGBA_INTEGER, gl::BYTE),
+            TextureFormat::RGBA8UI => (gl::RGBA8UI, gl::RGBA_INTEGER, gl::UNSIGNED_BYTE),
+            TextureFormat::RGBA32I => (gl::RGBA32I, gl::RGBA_INTEGER, gl::INT),
+            TextureFormat::RGBA32UI => (gl::RGBA32UI, gl::RGBA_INTEGER, gl::UNSIGNED_INT),
+            TextureFormat::DEPTH_COMPONENT16 => (gl::DEPTH_COMPONENT16, gl::DEPTH_COMPONENT, gl::UNSIGNED_SHORT),
+            TextureFormat::DEPTH_COMPONENT24 => (gl::DEPTH_COMPONENT24, gl::DEPTH_COMPONENT, gl::UNSIGNED_INT),
+            TextureFormat::DEPTH_COMPONENT32F => (gl::DEPTH_COMPONENT32F, gl::DEPTH_COMPONENT, gl::FLOAT),
+            TextureFormat::DEPTH24_STENCIL8 => (gl::DEPTH24_STENCIL8, gl::DEPTH_STENCIL, gl::UNSIGNED_INT_24_8),
+            TextureFormat::DEPTH32F_STENCIL8 => (gl::DEPTH32F_STENCIL8, gl::DEPTH_STENCIL, gl::FLOAT_32_UNSIGNED_INT_24_8_REV),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_per_pixel() {
+        let cases = [
+            (TextureFormat::R8, 1),
+            (TextureFormat::RGB8, 3),
+            (TextureFormat::SRGB8_ALPHA8, 4),
+            (TextureFormat::RG16F, 4),
+            (TextureFormat::RGBA16F, 8),
+            (TextureFormat::RGB32F, 12),
+            (TextureFormat::RGBA32F, 16),
+            (TextureFormat::R8I, 1),
+            (TextureFormat::R16UI, 2),
+            (TextureFormat::RG32I, 8),
+            (TextureFormat::RGBA32UI, 16),
+            (TextureFormat::DEPTH_COMPONENT16, 2),
+            (TextureFormat::DEPTH_COMPONENT24, 4),
+            (TextureFormat::DEPTH_COMPONENT32F, 4),
+            // Packed formats store depth and stencil in a single element
+            (TextureFormat::DEPTH24_STENCIL8, 4),
+            (TextureFormat::DEPTH32F_STENCIL8, 8),
+        ];
+
+        for (format, bytes) in cases {
+            assert_eq!(format.bytes_per_pixel(), bytes, "{:?}", format);
+        }
+    }
+}
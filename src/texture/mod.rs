@@ -0,0 +1,277 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::os::raw::c_void;
+use std::ptr::null;
+#[cfg(feature = "image")]
+use std::path::Path;
+
+use gl::types::{GLenum, GLint, GLsizei, GLuint};
+
+#[cfg(feature = "image")]
+use crate::data::image::{self, Image, ImageError};
+use crate::gl_call;
+use crate::leak_tracking::{self, GlObjectKind};
+use crate::shader::UnsupportedFeatureError;
+
+pub use format::TextureFormat;
+pub use sampler::{Filter, MipmapMode, Sampler, SamplerDesc, WrapMode};
+
+mod format;
+mod sampler;
+
+#[derive(Debug)]
+pub enum TextureError {
+    /// The length of the pixel data does not match width × height × bytes per pixel of the format
+    DataSizeMismatch {
+        format: TextureFormat,
+        width: u32,
+        height: u32,
+        expected: usize,
+        actual: usize,
+    },
+    #[cfg(feature = "image")]
+    Image(ImageError),
+}
+
+/// How shaders access a texture bound to an image unit
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageAccess {
+    READ_ONLY,
+    WRITE_ONLY,
+    READ_WRITE,
+}
+
+/// A 2D texture that owns its OpenGL texture object.
+///
+/// Textures are independent of shader programs. To sample a texture in a shader, it is bound to a texture unit
+/// and the unit is assigned to the program's sampler uniform, see `ShaderProgram::try_bind_texture`.
+pub struct Texture2D {
+    opengl_id: GLuint,
+    width: u32,
+    height: u32,
+    format: TextureFormat,
+    has_mipmaps: bool,
+}
+
+impl Texture2D {
+    /// Creates a texture from tightly packed pixels of the given format with the default sampling parameters.
+    /// The first row of the data is the row at texture coordinate t = 0.
+    ///
+    /// Integer and depth textures are created without mipmaps and with nearest filtering.
+    pub fn new(width: u32, height: u32, format: TextureFormat, data: &[u8]) -> Result<Self, TextureError> {
+        let sampler = if format.is_integer() || format.is_depth() {
+            SamplerDesc::new()
+                .filter(Filter::NEAREST)
+                .mipmap_mode(MipmapMode::NONE)
+        } else {
+            SamplerDesc::default()
+        };
+
+        Self::with_sampler(width, height, format, data, &sampler)
+    }
+
+    /// Creates a texture from tightly packed pixels of the given format, that is sampled as described by `sampler`
+    pub fn with_sampler(width: u32, height: u32, format: TextureFormat, data: &[u8], sampler: &SamplerDesc) -> Result<Self, TextureError> {
+        check_data_size(width, height, format, data)?;
+
+        let (internal_format, pixel_format, pixel_type) = format.to_gl_enums();
+
+        let mut opengl_id: GLuint = 0;
+        gl_call!(gl::GenTextures(1, &mut opengl_id));
+        leak_tracking::track_created(GlObjectKind::Texture, opengl_id);
+        gl_call!(gl::BindTexture(gl::TEXTURE_2D, opengl_id));
+
+        // Rows are tightly packed, which the default alignment of 4 bytes only allows for some formats and widths
+        gl_call!(gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1));
+        gl_call!(gl::TexImage2D(gl::TEXTURE_2D, 0, internal_format as GLint, width as GLsizei, height as GLsizei, 0, pixel_format, pixel_type, data.as_ptr() as *const c_void));
+        gl_call!(gl::PixelStorei(gl::UNPACK_ALIGNMENT, 4));
+
+        let mut texture = Self {
+            opengl_id,
+            width,
+            height,
+            format,
+            has_mipmaps: false,
+        };
+        texture.set_sampler(sampler);
+
+        Ok(texture)
+    }
+
+    /// Creates a texture of the given format without initializing its contents, e.g. to be used as framebuffer attachment.
+    ///
+    /// The texture is created without mipmaps and with linear filtering, or nearest filtering for integer formats.
+    pub fn empty(width: u32, height: u32, format: TextureFormat) -> Self {
+        let mut opengl_id: GLuint = 0;
+        gl_call!(gl::GenTextures(1, &mut opengl_id));
+        leak_tracking::track_created(GlObjectKind::Texture, opengl_id);
+
+        let mut texture = Self {
+            opengl_id,
+            width,
+            height,
+            format,
+            has_mipmaps: false,
+        };
+        texture.resize(width, height);
+
+        let filter = if format.is_integer() { Filter::NEAREST } else { Filter::LINEAR };
+        texture.set_sampler(&SamplerDesc::new()
+            .wrap(WrapMode::CLAMP_TO_EDGE)
+            .filter(filter)
+            .mipmap_mode(MipmapMode::NONE));
+
+        texture
+    }
+
+    /// Reallocates the storage of this texture with the given size. The contents of the texture are undefined afterwards.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        let (internal_format, pixel_format, pixel_type) = self.format.to_gl_enums();
+
+        gl_call!(gl::BindTexture(gl::TEXTURE_2D, self.opengl_id));
+        gl_call!(gl::TexImage2D(gl::TEXTURE_2D, 0, internal_format as GLint, width as GLsizei, height as GLsizei, 0, pixel_format, pixel_type, null()));
+
+        self.width = width;
+        self.height = height;
+        self.has_mipmaps = false;
+    }
+
+    /// Loads a texture from a PNG, JPEG, BMP, TGA or HDR file with the default sampling parameters
+    #[cfg(feature = "image")]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, TextureError> {
+        let image = image::load_from_path(path).map_err(TextureError::Image)?;
+        Self::from_image(&image)
+    }
+
+    #[cfg(feature = "image")]
+    pub fn from_image(image: &Image) -> Result<Self, TextureError> {
+        Self::new(image.width, image.height, image.format, &image.data)
+    }
+
+    /// Replaces the sampling parameters of this texture, mipmaps are generated if they are required and missing
+    pub fn set_sampler(&mut self, sampler: &SamplerDesc) {
+        gl_call!(gl::BindTexture(gl::TEXTURE_2D, self.opengl_id));
+
+        sampler.apply(gl::TEXTURE_2D, gl::TexParameteri, gl::TexParameterf, gl::TexParameterfv);
+
+        if sampler.mipmap_mode != MipmapMode::NONE && !self.has_mipmaps {
+            gl_call!(gl::GenerateMipmap(gl::TEXTURE_2D));
+            self.has_mipmaps = true;
+        }
+    }
+
+    /// Binds this texture to the given texture unit
+    pub fn bind(&self, unit: u32) {
+        gl_call!(gl::ActiveTexture(gl::TEXTURE0 + unit));
+        gl_call!(gl::BindTexture(gl::TEXTURE_2D, self.opengl_id));
+    }
+
+    /// Binds the base level of this texture to the given image unit, for image load/store in shaders.
+    /// The image uniform's format qualifier must match the format of the texture.
+    pub fn bind_image(&self, unit: u32, access: ImageAccess) -> Result<(), UnsupportedFeatureError> {
+        UnsupportedFeatureError::check("Image load/store", 4, 2, "GL_ARB_shader_image_load_store")?;
+
+        let (internal_format, _, _) = self.format.to_gl_enums();
+        gl_call!(gl::BindImageTexture(unit, self.opengl_id, 0, gl::FALSE, 0, access.to_gl_enum(), internal_format));
+        Ok(())
+    }
+
+    pub fn opengl_id(&self) -> GLuint {
+        self.opengl_id
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn format(&self) -> TextureFormat {
+        self.format
+    }
+}
+
+impl ImageAccess {
+    fn to_gl_enum(self) -> GLenum {
+        match self {
+            ImageAccess::READ_ONLY => gl::READ_ONLY,
+            ImageAccess::WRITE_ONLY => gl::WRITE_ONLY,
+            ImageAccess::READ_WRITE => gl::READ_WRITE,
+        }
+    }
+}
+
+/// Checks that `data` holds exactly the tightly packed pixels of a texture of the given size and format
+fn check_data_size(width: u32, height: u32, format: TextureFormat, data: &[u8]) -> Result<(), TextureError> {
+    let expected = width as usize * height as usize * format.bytes_per_pixel();
+    if data.len() != expected {
+        return Err(TextureError::DataSizeMismatch { format, width, height, expected, actual: data.len() });
+    }
+    Ok(())
+}
+
+impl Drop for Texture2D {
+    fn drop(&mut self) {
+        gl_call!(gl::DeleteTextures(1, &self.opengl_id));
+        leak_tracking::track_deleted(GlObjectKind::Texture, self.opengl_id);
+    }
+}
+
+impl Display for TextureError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TextureError::DataSizeMismatch { format, width, height, expected, actual } => {
+                write!(f, "[Bowl] Texture data of size {} does not match a {}x{} texture of format {:?}, which requires {} bytes", actual, width, height, format, expected)
+            }
+            #[cfg(feature = "image")]
+            TextureError::Image(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl Error for TextureError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            #[cfg(feature = "image")]
+            TextureError::Image(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_too_short_data() {
+        let result = check_data_size(4, 2, TextureFormat::RGBA8, &[0; 31]);
+        assert!(matches!(result, Err(TextureError::DataSizeMismatch { expected: 32, actual: 31, .. })), "{:?}", result);
+    }
+
+    #[test]
+    fn rejects_too_long_data() {
+        let result = check_data_size(4, 2, TextureFormat::RGBA8, &[0; 33]);
+        assert!(matches!(result, Err(TextureError::DataSizeMismatch { expected: 32, actual: 33, .. })), "{:?}", result);
+    }
+
+    #[test]
+    fn accepts_exact_data() {
+        // Rows of 3 × 3 bytes are not 4 byte aligned, but tightly packed
+        assert!(check_data_size(3, 2, TextureFormat::RGB8, &[0; 18]).is_ok());
+        assert!(check_data_size(0, 0, TextureFormat::RGBA8, &[]).is_ok());
+    }
+
+    #[test]
+    fn checks_float_and_packed_formats() {
+        assert!(check_data_size(2, 2, TextureFormat::RGBA16F, &[0; 32]).is_ok());
+        assert!(check_data_size(2, 2, TextureFormat::RGB32F, &[0; 48]).is_ok());
+        assert!(check_data_size(2, 2, TextureFormat::DEPTH24_STENCIL8, &[0; 16]).is_ok());
+
+        let result = check_data_size(2, 2, TextureFormat::DEPTH32F_STENCIL8, &[0; 16]);
+        assert!(matches!(result, Err(TextureError::DataSizeMismatch { expected: 32, actual: 16, .. })), "{:?}", result);
+    }
+}
@@ -0,0 +1,216 @@
+use std::cell::Cell;
+
+use gl::types::{GLenum, GLfloat, GLint, GLuint};
+use glam::Vec4;
+use glfw::ffi::{glfwGetCurrentContext, GLFWwindow};
+
+use crate::gl_call;
+use crate::leak_tracking::{self, GlObjectKind};
+use crate::util::is_extension_supported;
+
+// From GL_EXT_texture_filter_anisotropic, core since OpenGL 4.6
+const TEXTURE_MAX_ANISOTROPY: GLenum = 0x84FE;
+const MAX_TEXTURE_MAX_ANISOTROPY: GLenum = 0x84FF;
+
+thread_local! {
+    /// Whether anisotropic filtering is supported, cached for the context that was current when it was queried
+    static ANISOTROPIC_FILTERING_SUPPORT: Cell<Option<(*mut GLFWwindow, bool)>> = const { Cell::new(None) };
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WrapMode {
+    REPEAT,
+    MIRRORED_REPEAT,
+    CLAMP_TO_EDGE,
+    /// Texture coordinates outside of the texture sample the border color of the sampler
+    CLAMP_TO_BORDER,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Filter {
+    NEAREST,
+    LINEAR,
+}
+
+/// How the mipmap levels of a texture are sampled during minification
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MipmapMode {
+    /// No mipmaps are generated, only the base level is sampled
+    NONE,
+    NEAREST,
+    LINEAR,
+}
+
+/// Describes how a texture is sampled.
+///
+/// It is either applied to a texture when it is created, or used to create a `Sampler` object that overrides
+/// the sampling parameters of any texture bound to the same texture unit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SamplerDesc {
+    pub wrap_s: WrapMode,
+    pub wrap_t: WrapMode,
+    pub wrap_r: WrapMode,
+    pub border_color: Vec4,
+    pub min_filter: Filter,
+    pub mag_filter: Filter,
+    pub mipmap_mode: MipmapMode,
+    pub lod_bias: f32,
+    /// Values above 1.0 enable anisotropic filtering, if the extension is supported.
+    /// The value is clamped to the maximum supported by the driver.
+    pub max_anisotropy: f32,
+}
+
+impl SamplerDesc {
+    pub fn new() -> Self {
+        Self {
+            wrap_s: WrapMode::REPEAT,
+            wrap_t: WrapMode::REPEAT,
+            wrap_r: WrapMode::REPEAT,
+            border_color: Vec4::ZERO,
+            min_filter: Filter::LINEAR,
+            mag_filter: Filter::LINEAR,
+            mipmap_mode: MipmapMode::LINEAR,
+            lod_bias: 0.0,
+            max_anisotropy: 1.0,
+        }
+    }
+
+    /// Sets the wrap mode of all texture coordinates
+    pub fn wrap(mut self, wrap_mode: WrapMode) -> Self {
+        self.wrap_s = wrap_mode;
+        self.wrap_t = wrap_mode;
+        self.wrap_r = wrap_mode;
+        return self;
+    }
+    pub fn border_color(mut self, border_color: Vec4) -> Self {
+        self.border_color = border_color;
+        return self;
+    }
+    /// Sets the minification and magnification filter
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.min_filter = filter;
+        self.mag_filter = filter;
+        return self;
+    }
+    pub fn mipmap_mode(mut self, mipmap_mode: MipmapMode) -> Self {
+        self.mipmap_mode = mipmap_mode;
+        return self;
+    }
+    pub fn lod_bias(mut self, lod_bias: f32) -> Self {
+        self.lod_bias = lod_bias;
+        return self;
+    }
+    pub fn max_anisotropy(mut self, max_anisotropy: f32) -> Self {
+        self.max_anisotropy = max_anisotropy;
+        return self;
+    }
+
+    /// Applies all parameters through the given `glTexParameter*` or `glSamplerParameter*` style functions
+    pub(crate) fn apply(&self,
+                        target: GLuint,
+                        set_parameter_i: unsafe fn(GLuint, GLenum, GLint),
+                        set_parameter_f: unsafe fn(GLuint, GLenum, GLfloat),
+                        set_parameter_fv: unsafe fn(GLuint, GLenum, *const GLfloat)) {
+        gl_call!(set_parameter_i(target, gl::TEXTURE_WRAP_S, self.wrap_s.to_gl_enum() as GLint));
+        gl_call!(set_parameter_i(target, gl::TEXTURE_WRAP_T, self.wrap_t.to_gl_enum() as GLint));
+        gl_call!(set_parameter_i(target, gl::TEXTURE_WRAP_R, self.wrap_r.to_gl_enum() as GLint));
+        gl_call!(set_parameter_fv(target, gl::TEXTURE_BORDER_COLOR, self.border_color.to_array().as_ptr()));
+        gl_call!(set_parameter_i(target, gl::TEXTURE_MIN_FILTER, self.min_filter_gl_enum() as GLint));
+        gl_call!(set_parameter_i(target, gl::TEXTURE_MAG_FILTER, self.mag_filter.to_gl_enum() as GLint));
+        gl_call!(set_parameter_f(target, gl::TEXTURE_LOD_BIAS, self.lod_bias));
+
+        if self.max_anisotropy > 1.0 && is_anisotropic_filtering_supported() {
+            let mut maximum: GLfloat = 1.0;
+            gl_call!(gl::GetFloatv(MAX_TEXTURE_MAX_ANISOTROPY, &mut maximum));
+            gl_call!(set_parameter_f(target, TEXTURE_MAX_ANISOTROPY, self.max_anisotropy.min(maximum)));
+        }
+    }
+
+    fn min_filter_gl_enum(&self) -> GLenum {
+        match (self.min_filter, self.mipmap_mode) {
+            (Filter::NEAREST, MipmapMode::NONE) => gl::NEAREST,
+            (Filter::LINEAR, MipmapMode::NONE) => gl::LINEAR,
+            (Filter::NEAREST, MipmapMode::NEAREST) => gl::NEAREST_MIPMAP_NEAREST,
+            (Filter::LINEAR, MipmapMode::NEAREST) => gl::LINEAR_MIPMAP_NEAREST,
+            (Filter::NEAREST, MipmapMode::LINEAR) => gl::NEAREST_MIPMAP_LINEAR,
+            (Filter::LINEAR, MipmapMode::LINEAR) => gl::LINEAR_MIPMAP_LINEAR,
+        }
+    }
+}
+
+impl Default for SamplerDesc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An OpenGL sampler object.
+///
+/// While bound to a texture unit, its parameters are used instead of the parameters of the texture bound to that unit.
+/// This allows sampling the same texture differently in different draw calls.
+pub struct Sampler {
+    opengl_id: GLuint,
+}
+
+impl Sampler {
+    pub fn new(desc: &SamplerDesc) -> Self {
+        let mut opengl_id: GLuint = 0;
+        gl_call!(gl::GenSamplers(1, &mut opengl_id));
+        leak_tracking::track_created(GlObjectKind::Sampler, opengl_id);
+
+        desc.apply(opengl_id, gl::SamplerParameteri, gl::SamplerParameterf, gl::SamplerParameterfv);
+
+        Self {
+            opengl_id,
+        }
+    }
+
+    pub fn bind(&self, unit: u32) {
+        gl_call!(gl::BindSampler(unit, self.opengl_id));
+    }
+
+    /// Removes any sampler from the given texture unit, so that the parameters of the bound texture are used again
+    pub fn unbind(unit: u32) {
+        gl_call!(gl::BindSampler(unit, 0));
+    }
+}
+
+impl Drop for Sampler {
+    fn drop(&mut self) {
+        gl_call!(gl::DeleteSamplers(1, &self.opengl_id));
+        leak_tracking::track_deleted(GlObjectKind::Sampler, self.opengl_id);
+    }
+}
+
+impl WrapMode {
+    fn to_gl_enum(self) -> GLenum {
+        match self {
+            WrapMode::REPEAT => gl::REPEAT,
+            WrapMode::MIRRORED_REPEAT => gl::MIRRORED_REPEAT,
+            WrapMode::CLAMP_TO_EDGE => gl::CLAMP_TO_EDGE,
+            WrapMode::CLAMP_TO_BORDER => gl::CLAMP_TO_BORDER,
+        }
+    }
+}
+
+impl Filter {
+    fn to_gl_enum(self) -> GLenum {
+        match self {
+            Filter::NEAREST => gl::NEAREST,
+            Filter::LINEAR => gl::LINEAR,
+        }
+    }
+}
+
+fn is_anisotropic_filtering_supported() -> bool {
+    let context = unsafe { glfwGetCurrentContext() };
+    ANISOTROPIC_FILTERING_SUPPORT.with(|support| match support.get() {
+        Some((cached_context, supported)) if cached_context == context => supported,
+        _ => {
+            let supported = is_extension_supported("GL_EXT_texture_filter_anisotropic") || is_extension_supported("GL_ARB_texture_filter_anisotropic");
+            support.set(Some((context, supported)));
+            supported
+        }
+    })
+}
@@ -1,9 +1,38 @@
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
 use std::vec;
 
+use gl::types::{GLint, GLuint};
+
+use crate::gl_call;
+
 pub(crate) fn string_to_c_string(string: &str) -> CString {
     let mut bytes = vec![0; string.len() + 1];
     bytes[..string.len()].copy_from_slice(string.as_bytes());
 
     unsafe { CString::from_vec_with_nul_unchecked(bytes) }
-}
\ No newline at end of file
+}
+
+/// Checks whether the current OpenGL context supports the extension with the given name, e.g. `GL_ARB_buffer_storage`
+pub(crate) fn is_extension_supported(name: &str) -> bool {
+    let mut extension_count: GLint = 0;
+    gl_call!(gl::GetIntegerv(gl::NUM_EXTENSIONS, &mut extension_count));
+
+    (0..extension_count as GLuint).any(|index| {
+        let extension = gl_call!(gl::GetStringi(gl::EXTENSIONS, index));
+        !extension.is_null() && unsafe { CStr::from_ptr(extension as *const c_char) }.to_bytes() == name.as_bytes()
+    })
+}
+
+/// The version of the current OpenGL context as (major, minor)
+pub(crate) fn gl_version() -> (u32, u32) {
+    let (mut major, mut minor): (GLint, GLint) = (0, 0);
+    gl_call!(gl::GetIntegerv(gl::MAJOR_VERSION, &mut major));
+    gl_call!(gl::GetIntegerv(gl::MINOR_VERSION, &mut minor));
+    (major as u32, minor as u32)
+}
+
+/// Checks whether the current OpenGL context is at least of the given version, or supports the given extension
+pub(crate) fn is_supported(major: u32, minor: u32, extension: &str) -> bool {
+    gl_version() >= (major, minor) || is_extension_supported(extension)
+}
@@ -1,9 +1,14 @@
 use glam::{Vec2, Vec3};
 
+use crate::data::buffer::{Pod, Zeroable};
+use crate::data::vertex_buffer_layout::VertexLayout;
+
 /// This struct represents a single vertex.
 ///
-/// Note: The memory layout of this struct must match the layout
-/// of a vertex in the related shader written in GLSL
+/// Its attributes are bound to the locations 0 (position), 1 (color) and 2 (texture coordinates)
+/// of the related shader written in GLSL.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, VertexLayout)]
 pub struct Vertex {
     pub position: Vec3,
     pub color: Vec3,
@@ -9,8 +9,6 @@ pub fn init() {
             if glfwInit() == ffi::FALSE {
                 panic!("Could not initialize glfw");
             }
-            glfwWindowHint(ffi::CONTEXT_VERSION_MAJOR, 3);
-            glfwWindowHint(ffi::CONTEXT_VERSION_MINOR, 3);
             glfwWindowHint(ffi::OPENGL_PROFILE, ffi::OPENGL_CORE_PROFILE);
         }
     }
@@ -0,0 +1,230 @@
+use std::collections::HashSet;
+use std::mem::transmute;
+use std::os::raw::{c_double, c_int, c_uint};
+
+use glam::Vec2;
+use glfw::ffi;
+use glfw::ffi::GLFWwindow;
+
+use crate::window::window_state;
+
+pub use glfw::{Action, Key, Modifiers, MouseButton};
+
+/// A single input event, in the order it was received from GLFW during the current frame.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InputEvent {
+    Key { key: Key, scancode: i32, action: Action, modifiers: Modifiers },
+    Char(char),
+    MouseButton { button: MouseButton, action: Action, modifiers: Modifiers },
+    CursorMoved { position: Vec2 },
+    Scroll { delta: Vec2 },
+}
+
+/// Input state of a window, updated once per frame from the GLFW input callbacks.
+///
+/// `pressed` and `released` only hold for the single frame in which the corresponding event arrived,
+/// while `down` holds for as long as the key or button is held.
+pub struct Input {
+    keys_down: HashSet<Key>,
+    keys_pressed: HashSet<Key>,
+    keys_released: HashSet<Key>,
+    mouse_buttons_down: HashSet<MouseButton>,
+    mouse_buttons_pressed: HashSet<MouseButton>,
+    mouse_buttons_released: HashSet<MouseButton>,
+    cursor_position: Vec2,
+    window_size: Vec2,
+    scroll_delta: Vec2,
+    events: Vec<InputEvent>,
+}
+
+impl Input {
+    pub(crate) fn new(width: u32, height: u32) -> Self {
+        Self {
+            keys_down: HashSet::new(),
+            keys_pressed: HashSet::new(),
+            keys_released: HashSet::new(),
+            mouse_buttons_down: HashSet::new(),
+            mouse_buttons_pressed: HashSet::new(),
+            mouse_buttons_released: HashSet::new(),
+            cursor_position: Vec2::ZERO,
+            window_size: Vec2::new(width as f32, height as f32),
+            scroll_delta: Vec2::ZERO,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn is_key_down(&self, key: Key) -> bool {
+        self.keys_down.contains(&key)
+    }
+
+    pub fn is_key_pressed(&self, key: Key) -> bool {
+        self.keys_pressed.contains(&key)
+    }
+
+    pub fn is_key_released(&self, key: Key) -> bool {
+        self.keys_released.contains(&key)
+    }
+
+    pub fn is_mouse_button_down(&self, button: MouseButton) -> bool {
+        self.mouse_buttons_down.contains(&button)
+    }
+
+    pub fn is_mouse_button_pressed(&self, button: MouseButton) -> bool {
+        self.mouse_buttons_pressed.contains(&button)
+    }
+
+    pub fn is_mouse_button_released(&self, button: MouseButton) -> bool {
+        self.mouse_buttons_released.contains(&button)
+    }
+
+    /// The cursor position in pixels, relative to the top left corner of the window
+    pub fn cursor_position(&self) -> Vec2 {
+        self.cursor_position
+    }
+
+    /// The cursor position in normalized device coordinates, ranging from (-1, -1) in the bottom left
+    /// to (1, 1) in the top right corner of the window
+    pub fn cursor_position_ndc(&self) -> Vec2 {
+        if self.window_size.x <= 0.0 || self.window_size.y <= 0.0 {
+            return Vec2::ZERO;
+        }
+
+        Vec2::new(
+            2.0 * self.cursor_position.x / self.window_size.x - 1.0,
+            1.0 - 2.0 * self.cursor_position.y / self.window_size.y,
+        )
+    }
+
+    /// The accumulated scroll offset of the current frame
+    pub fn scroll_delta(&self) -> Vec2 {
+        self.scroll_delta
+    }
+
+    /// All input events received during the current frame
+    pub fn events(&self) -> &[InputEvent] {
+        &self.events
+    }
+
+    /// Resets all per-frame state. Must be called before polling the events of the next frame.
+    pub(crate) fn begin_frame(&mut self) {
+        self.keys_pressed.clear();
+        self.keys_released.clear();
+        self.mouse_buttons_pressed.clear();
+        self.mouse_buttons_released.clear();
+        self.scroll_delta = Vec2::ZERO;
+        self.events.clear();
+    }
+
+    fn on_key(&mut self, key: Key, scancode: i32, action: Action, modifiers: Modifiers) {
+        match action {
+            Action::Press => {
+                self.keys_down.insert(key);
+                self.keys_pressed.insert(key);
+            }
+            Action::Release => {
+                self.keys_down.remove(&key);
+                self.keys_released.insert(key);
+            }
+            Action::Repeat => {}
+        }
+        self.events.push(InputEvent::Key { key, scancode, action, modifiers });
+    }
+
+    fn on_mouse_button(&mut self, button: MouseButton, action: Action, modifiers: Modifiers) {
+        match action {
+            Action::Press => {
+                self.mouse_buttons_down.insert(button);
+                self.mouse_buttons_pressed.insert(button);
+            }
+            Action::Release => {
+                self.mouse_buttons_down.remove(&button);
+                self.mouse_buttons_released.insert(button);
+            }
+            Action::Repeat => {}
+        }
+        self.events.push(InputEvent::MouseButton { button, action, modifiers });
+    }
+}
+
+/// Registers all input callbacks for the given window.
+///
+/// The window's user pointer must point to the `WindowState` whose input should receive the events.
+pub(crate) fn register_callbacks(glfw_window: *mut GLFWwindow) {
+    unsafe {
+        ffi::glfwSetKeyCallback(glfw_window, Some(key_callback));
+        ffi::glfwSetCharCallback(glfw_window, Some(char_callback));
+        ffi::glfwSetMouseButtonCallback(glfw_window, Some(mouse_button_callback));
+        ffi::glfwSetCursorPosCallback(glfw_window, Some(cursor_position_callback));
+        ffi::glfwSetScrollCallback(glfw_window, Some(scroll_callback));
+        ffi::glfwSetWindowSizeCallback(glfw_window, Some(window_size_callback));
+    }
+}
+
+/// Removes all input callbacks registered by `register_callbacks`
+pub(crate) fn unregister_callbacks(glfw_window: *mut GLFWwindow) {
+    unsafe {
+        ffi::glfwSetKeyCallback(glfw_window, None);
+        ffi::glfwSetCharCallback(glfw_window, None);
+        ffi::glfwSetMouseButtonCallback(glfw_window, None);
+        ffi::glfwSetCursorPosCallback(glfw_window, None);
+        ffi::glfwSetScrollCallback(glfw_window, None);
+        ffi::glfwSetWindowSizeCallback(glfw_window, None);
+    }
+}
+
+fn input_of<'a>(glfw_window: *mut GLFWwindow) -> Option<&'a mut Input> {
+    window_state(glfw_window).map(|state| &mut state.input)
+}
+
+fn action_from_glfw(action: c_int) -> Action {
+    match action {
+        ffi::PRESS => Action::Press,
+        ffi::REPEAT => Action::Repeat,
+        _ => Action::Release,
+    }
+}
+
+extern "C" fn key_callback(glfw_window: *mut GLFWwindow, key: c_int, scancode: c_int, action: c_int, mods: c_int) {
+    // Keys that GLFW does not know about can not be represented by the Key enum
+    if key == ffi::KEY_UNKNOWN {
+        return;
+    }
+
+    if let Some(input) = input_of(glfw_window) {
+        let key: Key = unsafe { transmute(key) };
+        input.on_key(key, scancode, action_from_glfw(action), Modifiers::from_bits_truncate(mods));
+    }
+}
+
+extern "C" fn char_callback(glfw_window: *mut GLFWwindow, codepoint: c_uint) {
+    if let (Some(input), Some(character)) = (input_of(glfw_window), char::from_u32(codepoint)) {
+        input.events.push(InputEvent::Char(character));
+    }
+}
+
+extern "C" fn mouse_button_callback(glfw_window: *mut GLFWwindow, button: c_int, action: c_int, mods: c_int) {
+    if let (Some(input), Some(button)) = (input_of(glfw_window), MouseButton::from_i32(button)) {
+        input.on_mouse_button(button, action_from_glfw(action), Modifiers::from_bits_truncate(mods));
+    }
+}
+
+extern "C" fn cursor_position_callback(glfw_window: *mut GLFWwindow, x: c_double, y: c_double) {
+    if let Some(input) = input_of(glfw_window) {
+        input.cursor_position = Vec2::new(x as f32, y as f32);
+        input.events.push(InputEvent::CursorMoved { position: input.cursor_position });
+    }
+}
+
+extern "C" fn scroll_callback(glfw_window: *mut GLFWwindow, x: c_double, y: c_double) {
+    if let Some(input) = input_of(glfw_window) {
+        let delta = Vec2::new(x as f32, y as f32);
+        input.scroll_delta += delta;
+        input.events.push(InputEvent::Scroll { delta });
+    }
+}
+
+extern "C" fn window_size_callback(glfw_window: *mut GLFWwindow, width: c_int, height: c_int) {
+    if let Some(input) = input_of(glfw_window) {
+        input.window_size = Vec2::new(width as f32, height as f32);
+    }
+}
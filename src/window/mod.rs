@@ -1,23 +1,70 @@
 use core::option::Option;
-use std::os::raw::c_int;
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt::{Display, Formatter};
+use std::os::raw::{c_char, c_int, c_void};
+#[cfg(feature = "image")]
+use std::path::{Path, PathBuf};
 use std::ptr::null_mut;
 
-use gl::types::GLfloat;
-use glfw::ffi::{glfwCreateWindow, glfwGetProcAddress, glfwMakeContextCurrent, glfwPollEvents, glfwSetFramebufferSizeCallback, glfwSwapInterval, GLFWwindow, glfwWindowShouldClose};
+use gl::types::{GLfloat, GLsizei};
+use glam::Mat4;
+use glfw::ffi::{glfwCreateWindow, glfwDestroyWindow, glfwGetError, glfwGetFramebufferSize, glfwGetProcAddress, glfwGetWindowUserPointer, glfwMakeContextCurrent, glfwPollEvents, glfwSetFramebufferSizeCallback, glfwSetWindowUserPointer, glfwSwapInterval, GLFWwindow, glfwWindowHint, glfwWindowShouldClose};
 use glfw::with_c_str;
 
 use fps_timer::FPSTimer;
-
+use input::Input;
+
+use crate::camera::Camera;
+use crate::gl_call;
+use crate::leak_tracking;
+use crate::data::image::RgbaImage;
+use crate::framebuffer;
+use crate::framebuffer::{Framebuffer, FramebufferBuilder, FramebufferError};
 use crate::renderable::Renderable;
+use crate::shader;
 use crate::shader::ShaderProgram;
+use crate::texture::TextureFormat;
 use crate::util::string_to_c_string;
 
 mod fps_timer;
 mod glfw_initializer;
+pub mod input;
 
 pub struct Window {
     pub glfw_window: *mut GLFWwindow,
     pub max_fps: Option<f32>,
+    // Boxed, because its address is registered as the GLFW window user pointer for the callbacks
+    state: Box<WindowState>,
+    fps_timer: FPSTimer,
+    /// The framebuffer all frames are rendered into, if the window is headless
+    headless_framebuffer: Option<Framebuffer>,
+    /// Where to save a screenshot of the current frame once it is rendered completely
+    #[cfg(feature = "image")]
+    screenshot_path: Option<PathBuf>,
+}
+
+/// The part of a window that is updated by the GLFW callbacks
+pub(crate) struct WindowState {
+    pub(crate) input: Input,
+    pub(crate) camera: Camera,
+}
+
+#[derive(Debug)]
+pub enum WindowError {
+    /// The requested version is not an OpenGL version with a core profile, i.e. not between 3.2 and 4.6
+    UnsupportedGlVersion {
+        major: u32,
+        minor: u32,
+    },
+    /// GLFW could not create the window or its OpenGL context, e.g. because the driver does not support the
+    /// requested version
+    Creation {
+        gl_version: (u32, u32),
+        description: Option<String>,
+    },
+    /// The offscreen framebuffer of a headless window could not be created
+    HeadlessFramebuffer(FramebufferError),
 }
 
 pub struct WindowHandle<'a> {
@@ -32,6 +79,9 @@ pub struct WindowBuilder {
     fullscreen: bool,
     max_fps: Option<f32>,
     vsync: bool,
+    camera: Option<Camera>,
+    headless: bool,
+    gl_version: (u32, u32),
 }
 
 
@@ -44,6 +94,9 @@ impl WindowBuilder {
             fullscreen: false,
             max_fps: None,
             vsync: false,
+            camera: None,
+            headless: false,
+            gl_version: (3, 3),
         }
     }
 
@@ -68,10 +121,45 @@ impl WindowBuilder {
         self.vsync = vsync;
         return self;
     }
+    pub fn camera(mut self, camera: Camera) -> Self {
+        self.camera = Some(camera);
+        return self;
+    }
+    /// Creates an invisible window that renders into an offscreen framebuffer of the configured size.
+    ///
+    /// Headless windows are meant to be driven with `Window::frame` instead of `Window::run`. With the `egl` or
+    /// `osmesa` feature, the context is created through EGL or OSMesa instead of the native context API, which
+    /// allows rendering on machines without a display, if GLFW was built with support for it.
+    pub fn headless(mut self, headless: bool) -> Self {
+        self.headless = headless;
+        return self;
+    }
+
+    /// The version of the core profile context to create, 3.3 by default.
+    ///
+    /// Tessellation shaders require at least 4.0, image load/store at least 4.2 and compute shaders at least 4.3,
+    /// unless the driver supports them as extensions. Creating the window fails if the version is not supported.
+    /// Core profiles exist since OpenGL 3.2, so earlier versions are rejected with `WindowError::UnsupportedGlVersion`.
+    pub fn gl_version(mut self, major: u32, minor: u32) -> Self {
+        self.gl_version = (major, minor);
+        return self;
+    }
+
+    pub fn create(self) -> Result<Window, WindowError> {
+        let (major, minor) = self.gl_version;
+        if !matches!((major, minor), (3, 2..=3) | (4, 0..=6)) {
+            return Err(WindowError::UnsupportedGlVersion { major, minor });
+        }
 
-    pub fn create(self) -> Option<Window> {
         glfw_initializer::init();
 
+        unsafe {
+            glfwWindowHint(glfw::ffi::CONTEXT_VERSION_MAJOR, self.gl_version.0 as c_int);
+            glfwWindowHint(glfw::ffi::CONTEXT_VERSION_MINOR, self.gl_version.1 as c_int);
+            glfwWindowHint(glfw::ffi::VISIBLE, if self.headless { glfw::ffi::FALSE } else { glfw::ffi::TRUE });
+            glfwWindowHint(glfw::ffi::CONTEXT_CREATION_API, context_creation_api(self.headless));
+        }
+
         let glfw_window = unsafe {
             glfwCreateWindow(self.width as c_int,
                              self.height as c_int,
@@ -81,7 +169,7 @@ impl WindowBuilder {
         };
 
         if glfw_window.is_null() {
-            return None;
+            return Err(WindowError::Creation { gl_version: self.gl_version, description: glfw_error_description() });
         }
 
         // Focus window
@@ -96,53 +184,232 @@ impl WindowBuilder {
         // Setup viewport resize on window resize
         unsafe { glfwSetFramebufferSizeCallback(glfw_window, Some(update_viewport_size)); }
 
+        // Setup input callbacks
+        let mut state = Box::new(WindowState {
+            input: Input::new(self.width, self.height),
+            camera: self.camera.unwrap_or_default(),
+        });
+        unsafe { glfwSetWindowUserPointer(glfw_window, state.as_mut() as *mut WindowState as *mut c_void); }
+        input::register_callbacks(glfw_window);
+
+        // The framebuffer size may differ from the window size, e.g. on high DPI displays
+        let (mut framebuffer_width, mut framebuffer_height) = (0, 0);
+        unsafe { glfwGetFramebufferSize(glfw_window, &mut framebuffer_width, &mut framebuffer_height); }
+        state.camera.update_framebuffer_size(framebuffer_width, framebuffer_height);
+
+        let headless_framebuffer = if self.headless {
+            let framebuffer = FramebufferBuilder::new(self.width, self.height)
+                .color_texture(TextureFormat::RGBA8)
+                .depth_renderbuffer(TextureFormat::DEPTH24_STENCIL8)
+                .create();
+            match framebuffer {
+                Ok(framebuffer) => Some(framebuffer),
+                Err(error) => {
+                    // The window is not wrapped yet, so it has to be destroyed before the state it points to
+                    unsafe { glfwDestroyWindow(glfw_window); }
+                    return Err(WindowError::HeadlessFramebuffer(error));
+                }
+            }
+        } else {
+            None
+        };
+
         let window = Window {
             glfw_window,
             max_fps: self.max_fps,
+            state,
+            fps_timer: FPSTimer::new(self.max_fps),
+            headless_framebuffer,
+            #[cfg(feature = "image")]
+            screenshot_path: None,
         };
-        return Some(window);
+        window.bind_default_framebuffer();
+        return Ok(window);
     }
 }
 
 impl WindowHandle<'_> {
+    pub fn input(&self) -> &Input {
+        &self.window.state.input
+    }
+
+    pub fn camera(&self) -> &Camera {
+        &self.window.state.camera
+    }
+
+    pub fn camera_mut(&mut self) -> &mut Camera {
+        &mut self.window.state.camera
+    }
+
     pub fn render(&self, renderable: &impl Renderable, shader_program: &ShaderProgram) {
         shader_program.bind();
         renderable.render();
     }
 
-    pub fn render3d(&self, renderable: &impl Renderable, shader_program: &ShaderProgram) {
-        shader_program.bind();
+    /// Reads back everything rendered to the window in the current frame so far
+    pub fn capture(&self) -> RgbaImage {
+        self.window.capture()
+    }
+
+    /// Saves a screenshot of the current frame as PNG file, once the frame is rendered completely.
+    /// Errors are logged, as they can only occur after the frame closure returned.
+    #[cfg(feature = "image")]
+    pub fn request_screenshot<P: AsRef<Path>>(&mut self, path: P) {
+        self.window.screenshot_path = Some(path.as_ref().to_path_buf());
+    }
+
+    /// Renders the renderable into the given framebuffer instead of the window.
+    /// Afterwards the attachments of the framebuffer can be sampled as textures, e.g. with `Framebuffer::color_texture`.
+    pub fn render_to(&self, framebuffer: &Framebuffer, renderable: &impl Renderable, shader_program: &ShaderProgram) {
+        framebuffer.bind();
+        gl_call!(gl::Viewport(0, 0, framebuffer.width() as GLsizei, framebuffer.height() as GLsizei));
+
+        self.render(renderable, shader_program);
 
-        // TODO
+        self.window.bind_default_framebuffer();
+    }
 
+    /// Renders the renderable with the given model transform, using the window's camera for the view and projection.
+    ///
+    /// Errors of the matrix uniforms, e.g. if they are not of type mat4, are logged.
+    pub fn render3d(&self, renderable: &impl Renderable, transform: Mat4, shader_program: &ShaderProgram) {
+        shader_program.bind();
+        if let Err(error) = self.camera().upload_matrices(transform, shader_program) {
+            eprintln!("{}", error);
+        }
         renderable.render();
     }
 }
 
 impl Window {
+    /// The size of the window's default framebuffer in pixels, which may differ from the window size on high DPI displays.
+    /// For headless windows this is the size of the offscreen framebuffer.
+    pub fn framebuffer_size(&self) -> (u32, u32) {
+        if let Some(framebuffer) = &self.headless_framebuffer {
+            return (framebuffer.width(), framebuffer.height());
+        }
+
+        let (mut width, mut height) = (0, 0);
+        unsafe { glfwGetFramebufferSize(self.glfw_window, &mut width, &mut height); }
+        (width as u32, height as u32)
+    }
+
+    /// The offscreen framebuffer all frames are rendered into, if the window is headless
+    pub fn headless_framebuffer(&self) -> Option<&Framebuffer> {
+        self.headless_framebuffer.as_ref()
+    }
+
+    pub fn is_headless(&self) -> bool {
+        self.headless_framebuffer.is_some()
+    }
+
+    /// Reads back the contents of the framebuffer the current frame is rendered into
+    pub fn capture(&self) -> RgbaImage {
+        match &self.headless_framebuffer {
+            Some(framebuffer) => framebuffer.read_pixels(0),
+            None => {
+                let (width, height) = self.framebuffer_size();
+                framebuffer::read_pixels(0, gl::BACK, width, height)
+            }
+        }
+    }
+
+    pub fn should_close(&self) -> bool {
+        unsafe { glfwWindowShouldClose(self.glfw_window) != glfw::ffi::FALSE }
+    }
+
     pub fn run<F>(mut self, mut f: F)
         where F: FnMut(&mut WindowHandle) {
+        while !self.should_close() {
+            self.frame(&mut f);
+        }
+
+        // Objects moved into the closure are deleted before the window is dropped, so they are not reported as leaks
+        drop(f);
+    }
+
+    /// Processes events and renders a single frame, without blocking until the window is closed like `run` does
+    pub fn frame<F>(&mut self, f: F)
+        where F: FnOnce(&mut WindowHandle) {
         // In micros
-        let mut fps_timer = FPSTimer::new(self.max_fps);
+        let dtime = self.fps_timer.frame();
 
-        while unsafe { glfwWindowShouldClose(self.glfw_window) == glfw::ffi::FALSE } {
-            let dtime = fps_timer.frame();
+        self.state.input.begin_frame();
+        unsafe { glfwPollEvents(); }
 
-            unsafe { glfwPollEvents(); }
+        shader::reload_modified_programs();
 
-            gl_clear();
+        self.bind_default_framebuffer();
+        gl_clear();
 
-            let mut h = WindowHandle {
-                window: &mut self,
-                dtime,
-            };
-            f(&mut h);
+        let mut h = WindowHandle {
+            window: self,
+            dtime,
+        };
+        f(&mut h);
+
+        #[cfg(feature = "image")]
+        if let Some(path) = self.screenshot_path.take() {
+            if let Err(error) = self.capture().save_png(&path) {
+                eprintln!("[Bowl] Could not save screenshot to {}: {}", path.display(), error);
+            }
+        }
 
+        if !self.is_headless() {
             unsafe { glfw::ffi::glfwSwapBuffers(self.glfw_window); }
         }
     }
+
+    /// Binds the framebuffer frames are rendered into and resets the viewport to its size
+    fn bind_default_framebuffer(&self) {
+        match &self.headless_framebuffer {
+            Some(framebuffer) => framebuffer.bind(),
+            None => Framebuffer::unbind(),
+        }
+
+        let (width, height) = self.framebuffer_size();
+        gl_call!(gl::Viewport(0, 0, width as GLsizei, height as GLsizei));
+    }
+}
+
+impl Drop for Window {
+    fn drop(&mut self) {
+        // The headless framebuffer has to be deleted while its context still exists
+        unsafe { glfwMakeContextCurrent(self.glfw_window); }
+        self.headless_framebuffer = None;
+
+        leak_tracking::check();
+
+        // The state box is freed after this, so GLFW must not pass its address to any callback anymore
+        unsafe {
+            glfwSetWindowUserPointer(self.glfw_window, null_mut());
+            glfwSetFramebufferSizeCallback(self.glfw_window, None);
+        }
+        input::unregister_callbacks(self.glfw_window);
+
+        unsafe { glfwDestroyWindow(self.glfw_window); }
+    }
+}
+
+impl Display for WindowError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WindowError::UnsupportedGlVersion { major, minor } => {
+                write!(f, "[Bowl] OpenGL {}.{} is not supported, core profile contexts require a version from 3.2 to 4.6", major, minor)
+            }
+            WindowError::Creation { gl_version, description } => {
+                write!(f, "[Bowl] Could not create a window with an OpenGL {}.{} core profile context", gl_version.0, gl_version.1)?;
+                match description {
+                    Some(description) => write!(f, ": {}", description),
+                    None => Ok(()),
+                }
+            }
+            WindowError::HeadlessFramebuffer(error) => write!(f, "{}", error),
+        }
+    }
 }
 
+impl Error for WindowError {}
 
 fn gl_clear() {
     unsafe {
@@ -151,6 +418,31 @@ fn gl_clear() {
     }
 }
 
+/// The description of the last GLFW error, if there is one
+fn glfw_error_description() -> Option<String> {
+    let mut description: *const c_char = null_mut();
+    unsafe { glfwGetError(&mut description); }
+    if description.is_null() {
+        return None;
+    }
+    Some(unsafe { CStr::from_ptr(description) }.to_string_lossy().into_owned())
+}
+
+/// The context creation API selected by the `egl` and `osmesa` features, which only applies to headless windows
+fn context_creation_api(headless: bool) -> c_int {
+    if !headless {
+        return glfw::ffi::NATIVE_CONTEXT_API;
+    }
+
+    if cfg!(feature = "osmesa") {
+        glfw::ffi::OSMESA_CONTEXT_API
+    } else if cfg!(feature = "egl") {
+        glfw::ffi::EGL_CONTEXT_API
+    } else {
+        glfw::ffi::NATIVE_CONTEXT_API
+    }
+}
+
 fn setup_gl_function_loader() {
     gl::load_with(|name| {
         with_c_str(name, |c_name| unsafe {
@@ -159,8 +451,17 @@ fn setup_gl_function_loader() {
     });
 }
 
-extern "C" fn update_viewport_size(_window: *mut GLFWwindow, width: c_int, height: c_int) {
+/// Returns the state registered as user pointer of the given window
+pub(crate) fn window_state<'a>(glfw_window: *mut GLFWwindow) -> Option<&'a mut WindowState> {
+    unsafe { (glfwGetWindowUserPointer(glfw_window) as *mut WindowState).as_mut() }
+}
+
+extern "C" fn update_viewport_size(glfw_window: *mut GLFWwindow, width: c_int, height: c_int) {
     unsafe {
         gl::Viewport(0, 0, width, height);
     }
+
+    if let Some(state) = window_state(glfw_window) {
+        state.camera.update_framebuffer_size(width, height);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,164 @@
+//! Golden image tests of the examples, run with `cargo test --features testing --test golden`.
+//!
+//! The reference images in `tests/golden` are rendered with Mesa's software rasterizer, e.g. with
+//! `LIBGL_ALWAYS_SOFTWARE=1 xvfb-run cargo test --features testing --test golden`. The committed references were
+//! rendered with llvmpipe of Mesa 22.3.
+//! Set `BOWL_UPDATE_GOLDEN=1` to regenerate them after an intended change of the rendered output.
+//!
+//! This test has its own `main`, because GLFW must only be used from the main thread.
+
+use std::process::ExitCode;
+
+use glam::{Vec2, Vec3};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use bowl::data::buffer_mode::DrawMode;
+use bowl::data::indices_array::IndicesBuffer;
+use bowl::renderable::Mesh;
+use bowl::shader::{Shader, ShaderProgram};
+use bowl::shader::ShaderType::{FRAGMENT, VERTEX};
+use bowl::testing::{GoldenTest, GoldenTestError, GoldenTestOutcome};
+use bowl::texture::{SamplerDesc, Texture2D, WrapMode};
+use bowl::vertex::Vertex;
+
+use crate::circle::Circle;
+
+#[path = "../examples/custom_renderable/circle.rs"]
+mod circle;
+
+/// Covers differences in rasterization and interpolation between Mesa versions
+const TOLERANCE: u8 = 2;
+
+type GoldenTestFn = fn() -> Result<GoldenTestOutcome, GoldenTestError>;
+
+fn main() -> ExitCode {
+    let tests: [(&str, GoldenTestFn); 4] = [
+        ("circle", circle),
+        ("textured_triangle", textured_triangle),
+        ("random_triangles", random_triangles),
+        ("streamed_indices", streamed_indices),
+    ];
+
+    let (mut passed, mut written, mut failed) = (0, 0, 0);
+    for (name, test) in tests {
+        match test() {
+            Ok(GoldenTestOutcome::Matched) => {
+                println!("golden {} ... ok", name);
+                passed += 1;
+            }
+            Ok(GoldenTestOutcome::ReferenceWritten { path }) => {
+                println!("golden {} ... reference written to {}", name, path.display());
+                written += 1;
+            }
+            Err(error) => {
+                println!("golden {} ... FAILED\n    {}", name, error);
+                failed += 1;
+            }
+        }
+    }
+
+    if written > 0 {
+        println!("\ngolden test result: {} references written, nothing was compared; {} failed", written, failed);
+    } else {
+        println!("\ngolden test result: {} passed; {} failed", passed, failed);
+    }
+    if failed == 0 { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+}
+
+fn golden_test(name: &str) -> GoldenTest {
+    GoldenTest::new(name)
+        .size(256, 256)
+        .tolerance(TOLERANCE)
+        .reference_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden"))
+        .output_dir(concat!(env!("CARGO_TARGET_TMPDIR"), "/golden"))
+}
+
+fn circle() -> Result<GoldenTestOutcome, GoldenTestError> {
+    golden_test("circle").run(|| {
+        let circle = Circle::new(Vec2::new(0.0, 0.0), 0.8, 500);
+        let program = load_program(include_str!("../examples/custom_renderable/shader.vert"), include_str!("../examples/custom_renderable/shader.frag"));
+
+        move |handle| handle.render(&circle, &program)
+    })
+}
+
+fn textured_triangle() -> Result<GoldenTestOutcome, GoldenTestError> {
+    golden_test("textured_triangle").run(|| {
+        let mesh = Mesh::new(vec![
+            Vertex::from(Vec3::new(0.5, 0.5, 0.0), Vec3::splat(1.0), Vec2::new(1.0, 1.0)),
+            Vertex::from(Vec3::new(0.5, -0.5, 0.0), Vec3::splat(1.0), Vec2::new(1.0, 0.0)),
+            Vertex::from(Vec3::new(-0.5, -0.5, 0.0), Vec3::splat(1.0), Vec2::new(0.0, 0.0)),
+        ]);
+        let program = load_program(include_str!("../examples/textures/shader.vert"), include_str!("../examples/textures/shader.frag"));
+
+        let image = bowl::data::image::load_from_bytes(include_bytes!("../examples/textures/awesomeface.png")).expect("Could not load image!");
+        // No anisotropic filtering, as its support and implementation varies between drivers
+        let sampler = SamplerDesc::new().wrap(WrapMode::CLAMP_TO_EDGE);
+        let texture = Texture2D::with_sampler(image.width, image.height, image.format, &image.data, &sampler)
+            .expect("Could not create texture!");
+
+        move |handle| {
+            program.bind();
+            program.try_bind_texture("awesomefaceTexture", &texture, 0).expect("Could not bind texture!");
+            handle.render(&mesh, &program);
+        }
+    })
+}
+
+fn random_triangles() -> Result<GoldenTestOutcome, GoldenTestError> {
+    // One triangle is spawned per frame, the seed makes them the same in every run
+    let mut rng = StdRng::seed_from_u64(42);
+
+    golden_test("random_triangles").frames(8).run(|| {
+        let mut mesh = Mesh::new(Vec::new());
+        let program = load_program(include_str!("../examples/random_triangles/shader.vert"), include_str!("../examples/random_triangles/shader.frag"));
+
+        move |handle| {
+            mesh.add_vertices(random_triangle(&mut rng));
+            handle.render(&mesh, &program);
+        }
+    })
+}
+
+fn streamed_indices() -> Result<GoldenTestOutcome, GoldenTestError> {
+    // Each update is written to the next segment of the ring buffer, so drawing from the first segment would
+    // render the initial triangle instead of the last one
+    let updates: [[u16; 3]; 2] = [[1, 2, 3], [2, 3, 0]];
+
+    golden_test("streamed_indices").frames(updates.len() as u32).run(|| {
+        let mut mesh = Mesh::new(vec![
+            Vertex::from(Vec3::new(-0.5, -0.5, 0.0), Vec3::new(1.0, 0.0, 0.0), Vec2::ZERO),
+            Vertex::from(Vec3::new(0.5, -0.5, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec2::ZERO),
+            Vertex::from(Vec3::new(0.5, 0.5, 0.0), Vec3::new(0.0, 0.0, 1.0), Vec2::ZERO),
+            Vertex::from(Vec3::new(-0.5, 0.5, 0.0), Vec3::new(1.0, 1.0, 1.0), Vec2::ZERO),
+        ]);
+        mesh.set_index_buffer(IndicesBuffer::new(&[0u16, 1, 2], DrawMode::STREAM_PERSISTENT));
+        let program = load_program(include_str!("../examples/random_triangles/shader.vert"), include_str!("../examples/random_triangles/shader.frag"));
+
+        let mut updates = updates.into_iter();
+        move |handle| {
+            if let Some(indices) = updates.next() {
+                mesh.set_indices(&indices);
+            }
+            handle.render(&mesh, &program);
+        }
+    })
+}
+
+fn random_triangle(rng: &mut StdRng) -> Vec<Vertex> {
+    let triangle_color = Vec3::new(rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0));
+
+    (0..3)
+        .map(|_| {
+            let position = Vec3::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), 0.0);
+            Vertex::from(position, triangle_color, Vec2::new(0.0, 0.0))
+        })
+        .collect()
+}
+
+fn load_program(vertex_source: &str, fragment_source: &str) -> ShaderProgram {
+    let vertex_shader = Shader::new(VERTEX, vertex_source).expect("Could not compile vertex shader!");
+    let fragment_shader = Shader::new(FRAGMENT, fragment_source).expect("Could not compile fragment shader!");
+    ShaderProgram::new(vec![vertex_shader, fragment_shader]).expect("Could not link shader program!")
+}